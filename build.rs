@@ -0,0 +1,12 @@
+//! Exposes `KANI_SOLANA_TIER` as the `kani_solana_tier` cfg, so harness
+//! instances above the selected proof tier are compiled out.
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=KANI_SOLANA_TIER");
+    println!("cargo:rustc-check-cfg=cfg(kani_solana_tier, values(\"full\", \"exhaustive\"))");
+    if let Ok(tier) = std::env::var("KANI_SOLANA_TIER") {
+        if tier == "full" || tier == "exhaustive" {
+            println!("cargo:rustc-cfg=kani_solana_tier=\"{tier}\"");
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Auto-deleveraging (ADL) ranking and deficit allocation.
+//!
+//! When a liquidation leaves bad debt that the insurance fund cannot cover,
+//! Percolator-style engines close it against profitable counterparties
+//! instead of leaving it unbacked. Counterparties are ranked by
+//! `PnL × leverage` and the deficit is taken from the top `K` in rank order,
+//! never more than an account's positive PnL.
+//!
+//! Like [`risk`](crate::risk), these are plain `core` integer functions and
+//! are compiled into on-chain builds with the `risk` feature.
+//!
+//! Reference: https://github.com/aeyakovenko/percolator
+
+use crate::risk::{loss_writeoff, BPS_DENOMINATOR};
+
+/// Deficit left after the insurance fund absorbs what it can of
+/// `negative_equity`: the part [`loss_writeoff`] leaves unaccounted.
+#[must_use]
+pub fn uncovered_deficit(negative_equity: u128, insurance: u128) -> u128 {
+    let (writeoff, _) = loss_writeoff(negative_equity, insurance);
+    negative_equity - writeoff
+}
+
+/// ADL priority of one account: positive `pnl` times leverage in basis
+/// points (`notional * 10_000 / equity`).
+///
+/// Returns 0 for accounts without profit or without a position, so they are
+/// never deleveraged. Non-positive equity counts as maximal leverage.
+/// Saturates at `u128::MAX`.
+#[must_use]
+pub fn adl_score(pnl: i128, notional: u128, equity: i128) -> u128 {
+    if pnl <= 0 || notional == 0 {
+        return 0;
+    }
+    let leverage_bps = if equity <= 0 {
+        u128::MAX
+    } else {
+        notional.saturating_mul(BPS_DENOMINATOR as u128) / equity as u128
+    };
+    (pnl as u128).saturating_mul(leverage_bps.max(1))
+}
+
+/// Account indices ordered by descending `scores`; ties keep index order.
+#[must_use]
+pub fn adl_rank<const N: usize>(scores: &[u128; N]) -> [usize; N] {
+    let mut order = [0usize; N];
+    let mut i = 0;
+    while i < N {
+        order[i] = i;
+        i += 1;
+    }
+    // Insertion sort: stable and loop-bounded by `N` for Kani.
+    let mut i = 1;
+    while i < N {
+        let mut j = i;
+        while j > 0 && scores[order[j]] > scores[order[j - 1]] {
+            order.swap(j, j - 1);
+            j -= 1;
+        }
+        i += 1;
+    }
+    order
+}
+
+/// Result of allocating a deficit with [`adl_allocate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdlFill<const N: usize> {
+    /// Positive PnL taken from each account.
+    pub deleveraged: [u128; N],
+    /// Part of the deficit the top `K` accounts could not cover.
+    pub unfilled: u128,
+}
+
+impl<const N: usize> AdlFill<N> {
+    /// Sum of [`deleveraged`](Self::deleveraged).
+    pub fn total(&self) -> u128 {
+        let mut total: u128 = 0;
+        let mut i = 0;
+        while i < N {
+            total = total.saturating_add(self.deleveraged[i]);
+            i += 1;
+        }
+        total
+    }
+}
+
+/// Allocate `deficit` across the `K` highest-ranked accounts.
+///
+/// Walks [`adl_rank`] order and takes `min(positive pnl, remaining)` from each
+/// account with a non-zero score, so `total() + unfilled == deficit` and no
+/// account's PnL drops below zero. `K` larger than `N` is clamped.
+#[must_use]
+pub fn adl_allocate<const N: usize, const K: usize>(
+    pnl: &[i128; N],
+    scores: &[u128; N],
+    deficit: u128,
+) -> AdlFill<N> {
+    let order = adl_rank(scores);
+    let mut fill = AdlFill {
+        deleveraged: [0; N],
+        unfilled: deficit,
+    };
+    let mut r = 0;
+    while r < K.min(N) && fill.unfilled > 0 {
+        let i = order[r];
+        if scores[i] > 0 {
+            let take = (pnl[i].max(0) as u128).min(fill.unfilled);
+            fill.deleveraged[i] = take;
+            fill.unfilled -= take;
+        }
+        r += 1;
+    }
+    fill
+}
+
+#[cfg(kani)]
+mod proofs {
+    use super::*;
+    use crate::risk::{account_equity, position_notional};
+    use crate::tier::{any_signed, any_unsigned};
+
+    const N: usize = 4;
+    const K: usize = 2;
+
+    /// Draws an account table and its ADL scores at width `W`.
+    fn any_table<W: crate::tier::Width>() -> ([i128; N], [u128; N]) {
+        let mut pnl = [0i128; N];
+        let mut scores = [0u128; N];
+        let mut i = 0;
+        while i < N {
+            pnl[i] = any_signed::<W>();
+            let size = any_signed::<W>();
+            let price = any_unsigned::<W>();
+            let equity = account_equity(any_unsigned::<W>(), pnl[i], 1, 1, 0);
+            scores[i] = adl_score(pnl[i], position_notional(size, price), equity);
+            i += 1;
+        }
+        (pnl, scores)
+    }
+
+    crate::tiered_proof! {
+        #[cfg_attr(kani, kani::unwind(5))]
+        fn proof_adl_rank_is_sorted_permutation<W>() {
+            let (_, scores) = any_table::<W>();
+            let order = adl_rank(&scores);
+
+            let mut seen = [false; N];
+            let mut r = 0;
+            while r < N {
+                assert!(order[r] < N && !seen[order[r]]);
+                seen[order[r]] = true;
+                if r > 0 {
+                    assert!(scores[order[r - 1]] >= scores[order[r]]);
+                }
+                r += 1;
+            }
+        }
+    }
+
+    crate::tiered_proof! {
+        #[cfg_attr(kani, kani::unwind(5))]
+        fn proof_adl_fills_exactly_the_uncovered_deficit<W>() {
+            let (pnl, scores) = any_table::<W>();
+            let deficit = uncovered_deficit(any_unsigned::<W>(), any_unsigned::<W>());
+            let fill = adl_allocate::<N, K>(&pnl, &scores, deficit);
+
+            assert!(fill.total() + fill.unfilled == deficit);
+            crate::cover!(deficit > 0 && fill.unfilled == 0, "deficit fully deleveraged");
+
+            // A shortfall means every eligible top-K account gave all its profit.
+            let order = adl_rank(&scores);
+            let mut r = 0;
+            while r < K {
+                let i = order[r];
+                if fill.unfilled > 0 && scores[i] > 0 {
+                    assert!(fill.deleveraged[i] == pnl[i].max(0) as u128);
+                }
+                r += 1;
+            }
+        }
+    }
+
+    crate::tiered_proof! {
+        #[cfg_attr(kani, kani::unwind(5))]
+        fn proof_adl_never_takes_more_than_profit<W>() {
+            let (pnl, scores) = any_table::<W>();
+            let deficit = any_unsigned::<W>();
+            let fill = adl_allocate::<N, K>(&pnl, &scores, deficit);
+
+            let order = adl_rank(&scores);
+            let mut r = 0;
+            while r < N {
+                let i = order[r];
+                assert!(fill.deleveraged[i] <= pnl[i].max(0) as u128);
+                if r >= K || scores[i] == 0 {
+                    assert!(fill.deleveraged[i] == 0);
+                }
+                r += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,360 @@
+//! Constant-product (`x * y = k`) AMM reference swaps, LP share accounting
+//! and proof helpers.
+//!
+//! The fee is taken from the input before it reaches the curve, and every
+//! rounding step favours the pool: exact-in output rounds down, exact-out
+//! input rounds up. The helpers check a user's swap against the fee-adjusted
+//! invariant
+//!
+//! `(reserve_in * 10_000 + amount_in * (10_000 - fee_bps)) * (reserve_out - amount_out)
+//!     >= reserve_in * 10_000 * reserve_out`,
+//!
+//! which implies the raw product `k` never decreases.
+//!
+//! [`LpPool`] mints and burns LP shares pro rata, rounding against the
+//! depositor, and locks [`MINIMUM_LIQUIDITY`] on the first deposit against
+//! share-price inflation.
+
+use crate::generators::any_bps;
+use crate::tier::{any_unsigned, Width};
+
+const BPS: u128 = 10_000;
+
+/// Reference exact-in swap: `amount_in` of the input token, charged
+/// `fee_bps`, buys `amount_out` of the output token.
+///
+/// The fee is deducted from the input (rounding the effective input down) and
+/// the output rounds down. Returns `None` for an empty pool, `fee_bps > 10_000`
+/// or a post-swap input reserve that overflows `u64`.
+pub fn swap_exact_in(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    fee_bps: u64,
+) -> Option<u64> {
+    if reserve_in == 0 || reserve_out == 0 || fee_bps as u128 > BPS {
+        return None;
+    }
+    reserve_in.checked_add(amount_in)?;
+    let effective_in = amount_in as u128 * (BPS - fee_bps as u128) / BPS;
+    let out = reserve_out as u128 * effective_in / (reserve_in as u128 + effective_in);
+    Some(out as u64)
+}
+
+/// Reference exact-out swap: the `amount_in` needed to buy exactly
+/// `amount_out`, including `fee_bps`.
+///
+/// Both the curve input and the fee gross-up round up. Returns `None` for an
+/// empty pool, `amount_out >= reserve_out`, `fee_bps >= 10_000` or an input
+/// that does not fit the post-swap `u64` reserve.
+pub fn swap_exact_out(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_out: u64,
+    fee_bps: u64,
+) -> Option<u64> {
+    if reserve_in == 0 || amount_out >= reserve_out || fee_bps as u128 >= BPS {
+        return None;
+    }
+    let effective_in =
+        (reserve_in as u128 * amount_out as u128).div_ceil((reserve_out - amount_out) as u128);
+    let effective_in = u64::try_from(effective_in).ok()?;
+    let amount_in = (effective_in as u128 * BPS).div_ceil(BPS - fee_bps as u128);
+    let amount_in = u64::try_from(amount_in).ok()?;
+    reserve_in.checked_add(amount_in)?;
+    Some(amount_in)
+}
+
+/// Full 256-bit product of two `u128`s as `(high, low)`.
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a1, a0) = (a >> 64, a & MASK);
+    let (b1, b0) = (b >> 64, b & MASK);
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+    let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+    let low = (p00 & MASK) | (mid << 64);
+    let high = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    (high, low)
+}
+
+/// Whether a swap of `amount_in` for `amount_out` keeps the fee-adjusted
+/// product at or above `k`.
+fn preserves_k(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    amount_out: u64,
+    fee_bps: u64,
+) -> bool {
+    let scaled_in = reserve_in as u128 * BPS + amount_in as u128 * (BPS - fee_bps as u128);
+    let after = mul_wide(scaled_in, (reserve_out - amount_out) as u128);
+    let before = mul_wide(reserve_in as u128 * BPS, reserve_out as u128);
+    after >= before
+}
+
+/// Draws a token amount at width `W`, truncated to `u64`; tiered proofs over
+/// it stop at `u64` with `<W: u64>`.
+fn any_amount<W: Width>() -> u64 {
+    any_unsigned::<W>() as u64
+}
+
+/// Proves that `swap_fn(reserve_in, reserve_out, amount_in, fee_bps)`, an
+/// exact-in swap returning `amount_out`, never pays out the whole output
+/// reserve and never decreases the fee-adjusted `k`.
+///
+/// Reserves and amounts are drawn at width `W` (see
+/// [`tier`](crate::tier)) and fit `u64`, so tier it with `<W: u64>`. `None` means the swap was rejected and is not
+/// checked.
+pub fn assert_cp_swap_preserves_k<W: Width, F>(swap_fn: F)
+where
+    F: Fn(u64, u64, u64, u64) -> Option<u64>,
+{
+    let reserve_in = any_amount::<W>();
+    let reserve_out = any_amount::<W>();
+    let amount_in = any_amount::<W>();
+    let fee_bps = any_bps();
+    if let Some(amount_out) = swap_fn(reserve_in, reserve_out, amount_in, fee_bps) {
+        assert!(amount_out < reserve_out, "swap drains output reserve");
+        assert!(
+            preserves_k(reserve_in, reserve_out, amount_in, amount_out, fee_bps),
+            "swap decreases k"
+        );
+        crate::cover!(amount_out > 0, "swap pays out");
+    }
+}
+
+/// Proves that `swap_fn(reserve_in, reserve_out, amount_out, fee_bps)`, an
+/// exact-out swap returning `amount_in`, never pays out the whole output
+/// reserve and rounds the input up, so the fee-adjusted `k` never decreases.
+///
+/// Reserves and amounts are drawn at width `W` (see
+/// [`tier`](crate::tier)) and fit `u64`, so tier it with `<W: u64>`. `None` means the swap was rejected and is not
+/// checked.
+pub fn assert_cp_swap_exact_out_rounds_up<W: Width, F>(swap_fn: F)
+where
+    F: Fn(u64, u64, u64, u64) -> Option<u64>,
+{
+    let reserve_in = any_amount::<W>();
+    let reserve_out = any_amount::<W>();
+    let amount_out = any_amount::<W>();
+    let fee_bps = any_bps();
+    if let Some(amount_in) = swap_fn(reserve_in, reserve_out, amount_out, fee_bps) {
+        assert!(amount_out < reserve_out, "swap drains output reserve");
+        assert!(
+            preserves_k(reserve_in, reserve_out, amount_in, amount_out, fee_bps),
+            "exact-out input rounds down"
+        );
+        crate::cover!(amount_out > 0, "swap pays out");
+    }
+}
+
+/// Shares the first deposit locks in the pool forever, so the share supply
+/// never returns to zero and one share cannot be priced arbitrarily high.
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+/// Why an LP operation was rejected. Rejected operations leave the pool
+/// unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LpError {
+    /// The deposit would mint no shares.
+    ZeroShares,
+    /// Burn exceeds the unlocked share supply.
+    InsufficientShares,
+    /// A reserve or the share supply does not fit `u64`.
+    Overflow,
+}
+
+/// Two-token pool reserves and LP share supply, including the
+/// [`MINIMUM_LIQUIDITY`] locked by the first deposit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LpPool {
+    /// Reserve of token A.
+    pub reserve_a: u64,
+    /// Reserve of token B.
+    pub reserve_b: u64,
+    /// Outstanding LP shares.
+    pub supply: u64,
+}
+
+impl LpPool {
+    /// Empty pool: no reserves, no shares.
+    pub const fn new() -> Self {
+        Self {
+            reserve_a: 0,
+            reserve_b: 0,
+            supply: 0,
+        }
+    }
+
+    /// Deposit `amount_a` and `amount_b` and return the shares minted.
+    ///
+    /// The first deposit mints `isqrt(amount_a * amount_b)` shares and locks
+    /// [`MINIMUM_LIQUIDITY`] of them. Later deposits mint in proportion to the
+    /// smaller of the two deposit ratios, rounded down; the excess of the other
+    /// token stays in the pool. A deposit that would mint zero shares is
+    /// rejected instead of being absorbed.
+    pub fn mint(&mut self, amount_a: u64, amount_b: u64) -> Result<u64, LpError> {
+        let (shares, locked) = if self.supply == 0 {
+            let root = (amount_a as u128 * amount_b as u128).isqrt() as u64;
+            (root.saturating_sub(MINIMUM_LIQUIDITY), MINIMUM_LIQUIDITY)
+        } else if self.reserve_a == 0 || self.reserve_b == 0 {
+            (0, 0)
+        } else {
+            let supply = self.supply as u128;
+            let by_a = amount_a as u128 * supply / self.reserve_a as u128;
+            let by_b = amount_b as u128 * supply / self.reserve_b as u128;
+            let shares = u64::try_from(by_a.min(by_b)).map_err(|_| LpError::Overflow)?;
+            (shares, 0)
+        };
+        if shares == 0 {
+            return Err(LpError::ZeroShares);
+        }
+        let reserve_a = self
+            .reserve_a
+            .checked_add(amount_a)
+            .ok_or(LpError::Overflow)?;
+        let reserve_b = self
+            .reserve_b
+            .checked_add(amount_b)
+            .ok_or(LpError::Overflow)?;
+        let supply = self
+            .supply
+            .checked_add(shares + locked)
+            .ok_or(LpError::Overflow)?;
+        *self = Self {
+            reserve_a,
+            reserve_b,
+            supply,
+        };
+        Ok(shares)
+    }
+
+    /// Burn `shares` and return the pro-rata `(amount_a, amount_b)`, each
+    /// rounded down.
+    ///
+    /// The locked [`MINIMUM_LIQUIDITY`] can never be burned.
+    pub fn burn(&mut self, shares: u64) -> Result<(u64, u64), LpError> {
+        if shares == 0 {
+            return Ok((0, 0));
+        }
+        if shares > self.supply.saturating_sub(MINIMUM_LIQUIDITY) {
+            return Err(LpError::InsufficientShares);
+        }
+        let supply = self.supply as u128;
+        let amount_a = (shares as u128 * self.reserve_a as u128 / supply) as u64;
+        let amount_b = (shares as u128 * self.reserve_b as u128 / supply) as u64;
+        self.reserve_a -= amount_a;
+        self.reserve_b -= amount_b;
+        self.supply -= shares;
+        Ok((amount_a, amount_b))
+    }
+}
+
+#[cfg(kani)]
+mod proofs {
+    use super::*;
+
+    /// Symbolic pool that has taken its first deposit, drawn at width `W`.
+    ///
+    /// The supply is drawn above the locked [`MINIMUM_LIQUIDITY`], so narrow
+    /// widths still reach a live pool.
+    fn any_live_pool<W: Width>() -> LpPool {
+        let supply = (MINIMUM_LIQUIDITY + 1).checked_add(any_amount::<W>());
+        kani::assume(supply.is_some());
+        let pool = LpPool {
+            reserve_a: any_amount::<W>(),
+            reserve_b: any_amount::<W>(),
+            supply: supply.unwrap(),
+        };
+        kani::assume(pool.reserve_a > 0 && pool.reserve_b > 0);
+        crate::cover!(true, "live pool reached");
+        pool
+    }
+
+    /// Reserves per share never fall: `after.reserve / after.supply` is at
+    /// least `before.reserve / before.supply` for both tokens.
+    fn share_value_kept(before: &LpPool, after: &LpPool) -> bool {
+        let (s0, s1) = (before.supply as u128, after.supply as u128);
+        after.reserve_a as u128 * s0 >= before.reserve_a as u128 * s1
+            && after.reserve_b as u128 * s0 >= before.reserve_b as u128 * s1
+    }
+
+    crate::tiered_proof! {
+        fn proof_deposit_then_withdraw_never_profits<W: u64>() {
+            let mut pool = any_live_pool::<W>();
+            let (amount_a, amount_b) = (any_amount::<W>(), any_amount::<W>());
+            if let Ok(shares) = pool.mint(amount_a, amount_b) {
+                let (out_a, out_b) = pool.burn(shares).unwrap();
+                assert!(out_a <= amount_a && out_b <= amount_b);
+                crate::cover!(out_a == amount_a && out_b == amount_b, "exact round trip");
+            }
+        }
+    }
+
+    crate::tiered_proof! {
+        fn proof_lp_rounding_favours_pool<W: u64>() {
+            let before = any_live_pool::<W>();
+            let mut pool = before;
+            if kani::any() {
+                let (amount_a, amount_b) = (any_amount::<W>(), any_amount::<W>());
+                if let Ok(shares) = pool.mint(amount_a, amount_b) {
+                    let supply = before.supply as u128;
+                    assert!(shares as u128 * before.reserve_a as u128 <= amount_a as u128 * supply);
+                    assert!(shares as u128 * before.reserve_b as u128 <= amount_b as u128 * supply);
+                }
+            } else {
+                let shares = any_amount::<W>();
+                if let Ok((out_a, out_b)) = pool.burn(shares) {
+                    let supply = before.supply as u128;
+                    assert!(out_a as u128 * supply <= shares as u128 * before.reserve_a as u128);
+                    assert!(out_b as u128 * supply <= shares as u128 * before.reserve_b as u128);
+                }
+            }
+            assert!(share_value_kept(&before, &pool));
+        }
+    }
+
+    // The first mint needs `isqrt(amount_a * amount_b) > MINIMUM_LIQUIDITY`,
+    // out of reach of the narrow tiers, and the symbolic `isqrt` is heavy.
+    crate::__tier_full! {
+        #[kani::proof]
+        fn proof_donation_cannot_zero_out_second_depositor() {
+            // Attacker makes the first deposit, then donates to inflate the share price.
+            let mut pool = LpPool::new();
+            let Ok(attacker_shares) = pool.mint(kani::any(), kani::any()) else {
+                return;
+            };
+            let (donation_a, donation_b): (u64, u64) = (kani::any(), kani::any());
+            let (Some(reserve_a), Some(reserve_b)) = (
+                pool.reserve_a.checked_add(donation_a),
+                pool.reserve_b.checked_add(donation_b),
+            ) else {
+                return;
+            };
+            pool.reserve_a = reserve_a;
+            pool.reserve_b = reserve_b;
+
+            let (amount_a, amount_b): (u64, u64) = (kani::any(), kani::any());
+            let before = pool;
+            match pool.mint(amount_a, amount_b) {
+                Ok(shares) => assert!(shares > 0),
+                Err(LpError::ZeroShares) => {
+                    // The victim keeps their tokens, and pricing them out left more
+                    // than `MINIMUM_LIQUIDITY` times their deposit in the locked
+                    // shares, out of the attacker's reach.
+                    assert!(pool == before);
+                    let _ = pool.burn(attacker_shares).unwrap();
+                    let min = MINIMUM_LIQUIDITY as u128;
+                    assert!(
+                        pool.reserve_a as u128 > min * amount_a as u128
+                            || pool.reserve_b as u128 > min * amount_b as u128
+                    );
+                }
+                Err(LpError::Overflow | LpError::InsufficientShares) => {}
+            }
+        }
+    }
+}
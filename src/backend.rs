@@ -0,0 +1,74 @@
+//! Value source shared by every helper.
+//!
+//! Under `cargo kani` these forward to `kani::any` / `kani::assume`, so helpers
+//! explore every input symbolically. With the `runtime` feature in a normal
+//! build they forward to [`crate::runtime`], which samples concrete values and
+//! rejects samples that fail an assumption.
+//!
+//! Harness code that should run under both backends uses these functions
+//! instead of calling `kani` directly.
+
+#[cfg(kani)]
+pub use kani::Arbitrary;
+
+/// Arbitrary value of type `T`.
+#[cfg(kani)]
+#[inline(always)]
+pub fn any<T: Arbitrary>() -> T {
+    kani::any()
+}
+
+/// Restrict the explored inputs to those satisfying `cond`.
+#[cfg(kani)]
+#[inline(always)]
+pub fn assume(cond: bool) {
+    kani::assume(cond)
+}
+
+/// Values that [`any_in_range`] can draw from a closed interval.
+#[cfg(kani)]
+pub trait Ranged: Arbitrary + PartialOrd + Copy {}
+
+#[cfg(kani)]
+impl<T: Arbitrary + PartialOrd + Copy> Ranged for T {}
+
+/// Arbitrary value in `[min, max]` inclusive.
+///
+/// Prefer this over `any` + `assume` for ranges: the runtime backend samples
+/// the interval directly instead of rejecting out-of-range draws.
+#[cfg(kani)]
+#[inline(always)]
+pub fn any_in_range<T: Ranged>(min: T, max: T) -> T {
+    let v: T = kani::any();
+    kani::assume(v >= min && v <= max);
+    v
+}
+
+#[cfg(not(kani))]
+pub use crate::runtime::{any, any_in_range, assume, Arbitrary, Ranged};
+
+/// Check that `cond` is satisfiable, so the property guarded by it is not
+/// vacuously true.
+///
+/// Expands to `kani::cover!` under Kani; the runtime backend warns when a
+/// cover is never hit during `runtime::check`.
+#[cfg(kani)]
+#[macro_export]
+macro_rules! cover {
+    ($cond:expr, $msg:literal $(,)?) => {
+        kani::cover!($cond, $msg)
+    };
+}
+
+/// Check that `cond` is satisfiable, so the property guarded by it is not
+/// vacuously true.
+///
+/// Expands to `kani::cover!` under Kani; the runtime backend warns when a
+/// cover is never hit during `runtime::check`.
+#[cfg(not(kani))]
+#[macro_export]
+macro_rules! cover {
+    ($cond:expr, $msg:literal $(,)?) => {
+        $crate::runtime::cover($cond, $msg)
+    };
+}
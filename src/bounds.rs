@@ -1,41 +1,42 @@
 //! Proof helpers for bounded outputs and discrete value sets.
 
-/// Assert that a function's `u64` output is within `[min, max]`.
-pub fn assert_output_bounded<F>(compute_fn: F, min: u64, max: u64)
-where
-    F: FnOnce() -> u64,
-{
-    let result = compute_fn();
-    assert!(result >= min, "output below minimum bound");
-    assert!(result <= max, "output above maximum bound");
-}
+use core::fmt::Debug;
 
-/// Assert that a function's `u16` output is within `[min, max]`.
-pub fn assert_output_bounded_u16<F>(compute_fn: F, min: u16, max: u16)
-where
-    F: FnOnce() -> u16,
-{
-    let result = compute_fn();
-    assert!(result >= min, "output below minimum bound");
-    assert!(result <= max, "output above maximum bound");
+use crate::backend::{self, Arbitrary};
+use crate::risk::haircut_ratio;
+use crate::tier::{any_signed, any_unsigned, Width};
+
+/// Ordered numeric values accepted by the generic bound helpers.
+///
+/// Implemented for every primitive integer. Newtypes such as `Lamports(u64)`
+/// can opt in with `impl Numeric for Lamports {}` once they derive
+/// `Copy` and `PartialOrd`.
+pub trait Numeric: Copy + PartialOrd {}
+
+macro_rules! impl_numeric {
+    ($($t:ty),* $(,)?) => {
+        $(impl Numeric for $t {})*
+    };
 }
 
-/// Assert that a function's `u8` output is within `[min, max]`.
-pub fn assert_output_bounded_u8<F>(compute_fn: F, min: u8, max: u8)
+impl_numeric!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Assert that a function's output is within `[min, max]`.
+pub fn assert_bounded<T, F>(compute_fn: F, min: T, max: T)
 where
-    F: FnOnce() -> u8,
+    T: Numeric,
+    F: FnOnce() -> T,
 {
     let result = compute_fn();
     assert!(result >= min, "output below minimum bound");
     assert!(result <= max, "output above maximum bound");
 }
 
-/// Assert that a function's `u8` output is always one of the expected values.
-///
-/// Useful for stepped/tiered functions like refund percentages.
-pub fn assert_output_in_set<F>(compute_fn: F, expected: &[u8])
+/// Assert that a function's output is always one of the expected values.
+pub fn assert_in_set<T, F>(compute_fn: F, expected: &[T])
 where
-    F: FnOnce() -> u8,
+    T: Numeric,
+    F: FnOnce() -> T,
 {
     let result = compute_fn();
     let mut found = false;
@@ -53,23 +54,202 @@ where
 /// Assert that a cost/fee function has a floor and a ceiling.
 ///
 /// Verifies `floor <= result <= ceiling` for all symbolic inputs.
-pub fn assert_cost_bounded<F>(compute_fn: F, floor: u64, ceiling: u64)
+pub fn assert_cost_within<T, F>(compute_fn: F, floor: T, ceiling: T)
 where
-    F: FnOnce() -> u64,
+    T: Numeric,
+    F: FnOnce() -> T,
 {
     let result = compute_fn();
     assert!(result >= floor, "cost below floor");
     assert!(result <= ceiling, "cost above ceiling");
 }
 
-/// Assert that a function returns an expected default when a condition holds.
-pub fn assert_default_on_condition<C, F>(condition_fn: C, compute_fn: F, expected_default: u64)
+/// Assert `lo(&x) <= compute_fn(&x) <= hi(&x)` for every input `x` drawn by `gen`.
+///
+/// For relational properties where the bound depends on the input, e.g.
+/// "fee <= amount". Multi-argument functions take a tuple input:
+///
+/// ```ignore
+/// assert_output_bounded_by(
+///     |&(amount, bps)| fee(amount, bps),
+///     || (kani::any::<u64>(), any_bps()),
+///     |_| 0,
+///     |&(amount, _)| amount,
+/// );
+/// ```
+pub fn assert_output_bounded_by<I, T, F, G, L, H>(compute_fn: F, gen: G, lo: L, hi: H)
+where
+    T: Numeric,
+    F: FnOnce(&I) -> T,
+    G: FnOnce() -> I,
+    L: FnOnce(&I) -> T,
+    H: FnOnce(&I) -> T,
+{
+    let input = gen();
+    let result = compute_fn(&input);
+    assert!(result >= lo(&input), "output below input-dependent minimum");
+    assert!(result <= hi(&input), "output above input-dependent maximum");
+}
+
+/// [`assert_output_bounded_by`] over an unconstrained arbitrary input.
+pub fn assert_output_bounded_by_any<I, T, F, L, H>(compute_fn: F, lo: L, hi: H)
+where
+    I: Arbitrary,
+    T: Numeric,
+    F: FnOnce(&I) -> T,
+    L: FnOnce(&I) -> T,
+    H: FnOnce(&I) -> T,
+{
+    assert_output_bounded_by(compute_fn, backend::any::<I>, lo, hi);
+}
+
+/// Assert that a function's `u64` output is within `[min, max]`.
+pub fn assert_output_bounded<F>(compute_fn: F, min: u64, max: u64)
 where
-    C: FnOnce() -> bool,
     F: FnOnce() -> u64,
 {
-    if condition_fn() {
-        let result = compute_fn();
+    assert_bounded(compute_fn, min, max);
+}
+
+/// Assert that a function's `u16` output is within `[min, max]`.
+pub fn assert_output_bounded_u16<F>(compute_fn: F, min: u16, max: u16)
+where
+    F: FnOnce() -> u16,
+{
+    assert_bounded(compute_fn, min, max);
+}
+
+/// Assert that a function's `u8` output is within `[min, max]`.
+pub fn assert_output_bounded_u8<F>(compute_fn: F, min: u8, max: u8)
+where
+    F: FnOnce() -> u8,
+{
+    assert_bounded(compute_fn, min, max);
+}
+
+/// Assert that a function's `u8` output is always one of the expected values.
+///
+/// Useful for stepped/tiered functions like refund percentages.
+pub fn assert_output_in_set<F>(compute_fn: F, expected: &[u8])
+where
+    F: FnOnce() -> u8,
+{
+    assert_in_set(compute_fn, expected);
+}
+
+/// Assert that a `u64` cost/fee function has a floor and a ceiling.
+pub fn assert_cost_bounded<F>(compute_fn: F, floor: u64, ceiling: u64)
+where
+    F: FnOnce() -> u64,
+{
+    assert_cost_within(compute_fn, floor, ceiling);
+}
+
+/// Assert that `compute_fn(&x) == expected_default` whenever `condition_fn(&x)`
+/// holds, for every input `x` drawn by `gen`.
+///
+/// The input is drawn once and shared by both closures. A cover check reports
+/// whether the condition is reachable at all, so an unsatisfiable condition
+/// does not pass silently.
+pub fn assert_default_on_condition_by<I, T, F, G, C>(
+    compute_fn: F,
+    gen: G,
+    condition_fn: C,
+    expected_default: T,
+) where
+    T: PartialEq + Debug,
+    F: FnOnce(&I) -> T,
+    G: FnOnce() -> I,
+    C: FnOnce(&I) -> bool,
+{
+    let input = gen();
+    let condition = condition_fn(&input);
+    crate::cover!(condition, "default condition is reachable");
+    if condition {
+        let result = compute_fn(&input);
         assert_eq!(result, expected_default, "default value mismatch");
     }
 }
+
+/// [`assert_default_on_condition_by`] over an unconstrained arbitrary input.
+pub fn assert_default_on_condition_by_any<I, T, F, C>(
+    compute_fn: F,
+    condition_fn: C,
+    expected_default: T,
+) where
+    I: Arbitrary,
+    T: PartialEq + Debug,
+    F: FnOnce(&I) -> T,
+    C: FnOnce(&I) -> bool,
+{
+    assert_default_on_condition_by(
+        compute_fn,
+        backend::any::<I>,
+        condition_fn,
+        expected_default,
+    );
+}
+
+/// Assert that a `u64` function returns an expected default when a condition
+/// holds, with both closures evaluated on the same arbitrary input.
+///
+/// Shorthand for [`assert_default_on_condition_by_any`] with the condition
+/// first.
+pub fn assert_default_on_condition<I, C, F>(condition_fn: C, compute_fn: F, expected_default: u64)
+where
+    I: Arbitrary,
+    C: FnOnce(&I) -> bool,
+    F: FnOnce(&I) -> u64,
+{
+    assert_default_on_condition_by_any(compute_fn, condition_fn, expected_default);
+}
+
+/// Proves that a haircut payout function over `N` accounts pays out at most
+/// the residual, and loses at most `N - 1` units to rounding while the
+/// haircut is active.
+///
+/// Draws `N` PnL entries (negative ones included), vault, principal and
+/// insurance at width `W`, computes `h` with the real [`haircut_ratio`] and
+/// applies `payout_fn(pnl, h_num, h_den)` to every account. Pass
+/// [`effective_pnl`](crate::risk::effective_pnl) or a program's own payout
+/// function with the same signature. Inputs where `vault * pnl_pos_total`
+/// overflows `u128` are skipped, so the reference payouts stay exact.
+///
+/// Under Kani this needs `#[kani::unwind(N + 1)]`.
+pub fn assert_haircut_slack_bounded<W: Width, const N: usize>(
+    payout_fn: impl Fn(i128, u128, u128) -> u128,
+) {
+    let mut pnl = [0i128; N];
+    let mut pnl_pos_total: Option<u128> = Some(0);
+    let mut i = 0;
+    while i < N {
+        pnl[i] = any_signed::<W>();
+        pnl_pos_total = pnl_pos_total.and_then(|t| t.checked_add(pnl[i].max(0) as u128));
+        i += 1;
+    }
+    let vault = any_unsigned::<W>();
+    let principal_total = any_unsigned::<W>();
+    let insurance = any_unsigned::<W>();
+    let pnl_pos_total = pnl_pos_total.unwrap_or(u128::MAX);
+    backend::assume(vault.checked_mul(pnl_pos_total).is_some());
+
+    let (h_num, h_den) = haircut_ratio(vault, principal_total, insurance, pnl_pos_total);
+    crate::cover!(h_num < h_den, "haircut is active");
+
+    let mut paid: u128 = 0;
+    let mut i = 0;
+    while i < N {
+        paid = paid.saturating_add(payout_fn(pnl[i], h_num, h_den));
+        i += 1;
+    }
+
+    // Without a haircut every profit is paid in full, so the payable amount
+    // is the smaller of residual and total profit.
+    let residual = vault.saturating_sub(principal_total.saturating_add(insurance));
+    let payable = residual.min(pnl_pos_total);
+    assert!(paid <= payable, "payouts exceed residual");
+    assert!(
+        payable - paid <= (N as u128).saturating_sub(1),
+        "rounding slack exceeds account count"
+    );
+}
@@ -0,0 +1,498 @@
+//! Percolator-style reference engine built on the [`risk`](crate::risk) primitives.
+//!
+//! A fixed-capacity account table plus the global vault and insurance fund,
+//! with the operations that move value between them. The model exists to
+//! state and prove the global invariants the isolated primitives cannot:
+//!
+//! - Conservation: `vault >= principal_total + insurance + sum(effective positive PnL)`,
+//!   which implies the haircut never pays out more than the residual.
+//! - Principal protection: an operation on one account never reduces another
+//!   account's capital.
+//!
+//! Reference: https://github.com/aeyakovenko/percolator
+
+use crate::backend;
+use crate::risk::{
+    account_equity, effective_pnl, fee_debt_sweep, funding_payment, haircut_ratio, is_liquidatable,
+    loss_writeoff,
+};
+
+/// One account slot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Account {
+    /// Deposited principal, withdrawable once losses are settled.
+    pub capital: u128,
+    /// Unrealized PnL; positive PnL is paid out through the haircut.
+    pub pnl: i128,
+    /// Fees owed but not yet swept from capital.
+    pub fee_debt: u128,
+}
+
+/// Why an engine operation was rejected. Rejected operations leave the
+/// engine unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineError {
+    /// Account index is outside the table.
+    InvalidAccount,
+    /// Withdrawal exceeds capital after settlement.
+    InsufficientCapital,
+    /// Liquidation requested for an account that is not underwater.
+    NotLiquidatable,
+    /// An intermediate value does not fit its type.
+    Overflow,
+}
+
+/// Engine state with a fixed table of `N` accounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Engine<const N: usize> {
+    /// Tokens held by the program.
+    pub vault: u128,
+    /// Insurance fund, senior to positive PnL.
+    pub insurance: u128,
+    /// Account table.
+    pub accounts: [Account; N],
+}
+
+impl<const N: usize> Default for Engine<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Engine<N> {
+    /// Empty engine: no tokens, no insurance, all accounts zero.
+    pub const fn new() -> Self {
+        Self {
+            vault: 0,
+            insurance: 0,
+            accounts: [Account {
+                capital: 0,
+                pnl: 0,
+                fee_debt: 0,
+            }; N],
+        }
+    }
+
+    /// Sum of all account capital.
+    pub fn principal_total(&self) -> u128 {
+        let mut total: u128 = 0;
+        let mut i = 0;
+        while i < N {
+            total = total.saturating_add(self.accounts[i].capital);
+            i += 1;
+        }
+        total
+    }
+
+    /// Sum of positive account PnL.
+    pub fn pnl_pos_total(&self) -> u128 {
+        let mut total: u128 = 0;
+        let mut i = 0;
+        while i < N {
+            total = total.saturating_add(self.accounts[i].pnl.max(0) as u128);
+            i += 1;
+        }
+        total
+    }
+
+    /// Current global haircut `(h_num, h_den)`.
+    pub fn haircut(&self) -> (u128, u128) {
+        haircut_ratio(
+            self.vault,
+            self.principal_total(),
+            self.insurance,
+            self.pnl_pos_total(),
+        )
+    }
+
+    /// Sum of positive PnL after the haircut, i.e. what profitable accounts
+    /// could actually be paid right now.
+    pub fn effective_pnl_total(&self) -> u128 {
+        let (h_num, h_den) = self.haircut();
+        let mut total: u128 = 0;
+        let mut i = 0;
+        while i < N {
+            total = total.saturating_add(effective_pnl(self.accounts[i].pnl, h_num, h_den));
+            i += 1;
+        }
+        total
+    }
+
+    /// Conservation invariant:
+    /// `vault >= principal_total + insurance + effective_pnl_total`.
+    pub fn is_solvent(&self) -> bool {
+        let owed = self
+            .principal_total()
+            .checked_add(self.insurance)
+            .and_then(|x| x.checked_add(self.effective_pnl_total()));
+        match owed {
+            Some(owed) => self.vault >= owed,
+            None => false,
+        }
+    }
+
+    fn check_index(i: usize) -> Result<(), EngineError> {
+        if i < N {
+            Ok(())
+        } else {
+            Err(EngineError::InvalidAccount)
+        }
+    }
+
+    /// Realize account `i`'s PnL into capital: losses are paid from capital
+    /// first, positive PnL is converted at the current haircut.
+    ///
+    /// Losses larger than capital stay on the account as negative PnL until
+    /// [`Engine::liquidate`] writes them off.
+    fn settle(&mut self, i: usize) -> Result<(), EngineError> {
+        let pnl = self.accounts[i].pnl;
+        if pnl < 0 {
+            let loss = pnl.unsigned_abs().min(self.accounts[i].capital);
+            self.accounts[i].capital -= loss;
+            self.accounts[i].pnl = pnl.saturating_add_unsigned(loss);
+        } else if pnl > 0 {
+            let (h_num, h_den) = self.haircut();
+            let paid = effective_pnl(pnl, h_num, h_den);
+            self.accounts[i].capital = self.accounts[i]
+                .capital
+                .checked_add(paid)
+                .ok_or(EngineError::Overflow)?;
+            self.accounts[i].pnl = 0;
+        }
+        Ok(())
+    }
+
+    /// Deposit `amount` into account `i`.
+    pub fn deposit(&mut self, i: usize, amount: u128) -> Result<(), EngineError> {
+        Self::check_index(i)?;
+        let vault = self
+            .vault
+            .checked_add(amount)
+            .ok_or(EngineError::Overflow)?;
+        let capital = self.accounts[i]
+            .capital
+            .checked_add(amount)
+            .ok_or(EngineError::Overflow)?;
+        self.vault = vault;
+        self.accounts[i].capital = capital;
+        Ok(())
+    }
+
+    /// Settle account `i` and withdraw `amount` of its capital.
+    pub fn withdraw(&mut self, i: usize, amount: u128) -> Result<(), EngineError> {
+        Self::check_index(i)?;
+        let mut next = *self;
+        next.settle(i)?;
+        if amount > next.accounts[i].capital {
+            return Err(EngineError::InsufficientCapital);
+        }
+        next.accounts[i].capital -= amount;
+        next.vault = next
+            .vault
+            .checked_sub(amount)
+            .ok_or(EngineError::Overflow)?;
+        *self = next;
+        Ok(())
+    }
+
+    /// Settle a fill's PnL: `pnl` moves from `loser` to `winner`.
+    pub fn settle_trade(
+        &mut self,
+        winner: usize,
+        loser: usize,
+        pnl: u128,
+    ) -> Result<(), EngineError> {
+        Self::check_index(winner)?;
+        Self::check_index(loser)?;
+        let delta = i128::try_from(pnl).map_err(|_| EngineError::Overflow)?;
+        let mut next = *self;
+        next.accounts[loser].pnl = next.accounts[loser]
+            .pnl
+            .checked_sub(delta)
+            .ok_or(EngineError::Overflow)?;
+        next.accounts[winner].pnl = next.accounts[winner]
+            .pnl
+            .checked_add(delta)
+            .ok_or(EngineError::Overflow)?;
+        *self = next;
+        Ok(())
+    }
+
+    /// Apply one funding interval between a long and a short holding
+    /// `position` each, at rate `rate_num / rate_den` (positive: longs pay).
+    pub fn funding_crank(
+        &mut self,
+        long: usize,
+        short: usize,
+        position: u128,
+        rate_num: i128,
+        rate_den: u128,
+    ) -> Result<(), EngineError> {
+        Self::check_index(long)?;
+        Self::check_index(short)?;
+        let payment = funding_payment(position, rate_num, rate_den, true);
+        let mut next = *self;
+        next.accounts[long].pnl = next.accounts[long]
+            .pnl
+            .checked_sub(payment)
+            .ok_or(EngineError::Overflow)?;
+        next.accounts[short].pnl = next.accounts[short]
+            .pnl
+            .checked_add(payment)
+            .ok_or(EngineError::Overflow)?;
+        *self = next;
+        Ok(())
+    }
+
+    /// Charge `fee` to account `i` and sweep as much fee debt as its capital
+    /// covers into the insurance fund.
+    pub fn sweep_fees(&mut self, i: usize, fee: u128) -> Result<(), EngineError> {
+        Self::check_index(i)?;
+        let debt = self.accounts[i]
+            .fee_debt
+            .checked_add(fee)
+            .ok_or(EngineError::Overflow)?;
+        let (swept, remaining) = fee_debt_sweep(debt, self.accounts[i].capital);
+        let insurance = self
+            .insurance
+            .checked_add(swept)
+            .ok_or(EngineError::Overflow)?;
+        self.accounts[i].capital -= swept;
+        self.accounts[i].fee_debt = remaining;
+        self.insurance = insurance;
+        Ok(())
+    }
+
+    /// Equity of account `i` at the current haircut (see [`account_equity`]).
+    pub fn equity(&self, i: usize) -> Result<i128, EngineError> {
+        Self::check_index(i)?;
+        let (h_num, h_den) = self.haircut();
+        let account = self.accounts[i];
+        Ok(account_equity(
+            account.capital,
+            account.pnl,
+            h_num,
+            h_den,
+            account.fee_debt,
+        ))
+    }
+
+    /// Liquidate account `i`: settle its losses against capital, cover any
+    /// remaining bad debt from insurance and write off the rest.
+    ///
+    /// Only an underwater account qualifies: the engine tracks no positions,
+    /// so [`is_liquidatable`] applies with a zero maintenance requirement and
+    /// the account's equity must be negative. The uncovered part stays
+    /// unbacked and is absorbed by the haircut on other accounts' positive PnL.
+    pub fn liquidate(&mut self, i: usize) -> Result<(), EngineError> {
+        if !is_liquidatable(self.equity(i)?, 0, 0) {
+            return Err(EngineError::NotLiquidatable);
+        }
+        let mut next = *self;
+        next.settle(i)?;
+        let bad_debt = next.accounts[i].pnl.min(0).unsigned_abs();
+        let (_, new_insurance) = loss_writeoff(bad_debt, next.insurance);
+        next.insurance = new_insurance;
+        next.accounts[i].pnl = 0;
+        *self = next;
+        Ok(())
+    }
+
+    /// Apply one [`EngineOp`]. Account indices are taken modulo `N`.
+    pub fn apply(&mut self, op: &EngineOp) -> Result<(), EngineError> {
+        let idx = |a: u8| a as usize % N.max(1);
+        match *op {
+            EngineOp::Deposit { account, amount } => self.deposit(idx(account), amount as u128),
+            EngineOp::Withdraw { account, amount } => self.withdraw(idx(account), amount as u128),
+            EngineOp::SettleTrade { winner, loser, pnl } => {
+                self.settle_trade(idx(winner), idx(loser), pnl as u128)
+            }
+            EngineOp::FundingCrank {
+                long,
+                short,
+                position,
+                rate_num,
+                rate_den,
+            } => self.funding_crank(
+                idx(long),
+                idx(short),
+                position as u128,
+                rate_num as i128,
+                rate_den as u128,
+            ),
+            EngineOp::SweepFees { account, fee } => self.sweep_fees(idx(account), fee as u128),
+            EngineOp::Liquidate { account } => self.liquidate(idx(account)),
+        }
+    }
+}
+
+/// One engine operation with small operands, for bounded sequence proofs.
+///
+/// Operands are `u8`/`i8` to keep symbolic exploration tractable; the
+/// [`Engine`] methods themselves take full-width values.
+#[cfg_attr(kani, derive(kani::Arbitrary))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineOp {
+    /// See [`Engine::deposit`].
+    Deposit { account: u8, amount: u8 },
+    /// See [`Engine::withdraw`].
+    Withdraw { account: u8, amount: u8 },
+    /// See [`Engine::settle_trade`].
+    SettleTrade { winner: u8, loser: u8, pnl: u8 },
+    /// See [`Engine::funding_crank`].
+    FundingCrank {
+        long: u8,
+        short: u8,
+        position: u8,
+        rate_num: i8,
+        rate_den: u8,
+    },
+    /// See [`Engine::sweep_fees`].
+    SweepFees { account: u8, fee: u8 },
+    /// See [`Engine::liquidate`].
+    Liquidate { account: u8 },
+}
+
+impl EngineOp {
+    /// The account whose capital this operation may reduce, if any.
+    pub fn debited_account<const N: usize>(&self) -> Option<usize> {
+        let idx = |a: u8| a as usize % N.max(1);
+        match *self {
+            EngineOp::Withdraw { account, .. }
+            | EngineOp::SweepFees { account, .. }
+            | EngineOp::Liquidate { account } => Some(idx(account)),
+            EngineOp::Deposit { .. }
+            | EngineOp::SettleTrade { .. }
+            | EngineOp::FundingCrank { .. } => None,
+        }
+    }
+}
+
+#[cfg(not(kani))]
+impl backend::Arbitrary for EngineOp {
+    fn any() -> Self {
+        match backend::any_in_range(0u8, 5) {
+            0 => EngineOp::Deposit {
+                account: backend::any(),
+                amount: backend::any(),
+            },
+            1 => EngineOp::Withdraw {
+                account: backend::any(),
+                amount: backend::any(),
+            },
+            2 => EngineOp::SettleTrade {
+                winner: backend::any(),
+                loser: backend::any(),
+                pnl: backend::any(),
+            },
+            3 => EngineOp::FundingCrank {
+                long: backend::any(),
+                short: backend::any(),
+                position: backend::any(),
+                rate_num: backend::any(),
+                rate_den: backend::any(),
+            },
+            4 => EngineOp::SweepFees {
+                account: backend::any(),
+                fee: backend::any(),
+            },
+            _ => EngineOp::Liquidate {
+                account: backend::any(),
+            },
+        }
+    }
+}
+
+/// Symbolic engine satisfying the conservation invariant, with `u8`-sized
+/// balances and arbitrary (not necessarily zero-sum) PnL.
+pub fn any_solvent_engine<const N: usize>() -> Engine<N> {
+    let mut engine = Engine::<N>::new();
+    let mut owed: u128 = 0;
+    let mut i = 0;
+    while i < N {
+        engine.accounts[i] = Account {
+            capital: backend::any::<u8>() as u128,
+            pnl: backend::any::<i8>() as i128,
+            fee_debt: backend::any::<u8>() as u128,
+        };
+        owed += engine.accounts[i].capital;
+        i += 1;
+    }
+    engine.insurance = backend::any::<u8>() as u128;
+    engine.vault = owed + engine.insurance + backend::any::<u8>() as u128;
+    engine
+}
+
+/// Proves the conservation invariant after every step of every sequence of
+/// up to `K` operations from any solvent `N`-account engine.
+///
+/// Needs `#[kani::unwind]` of at least `max(N, K) + 1`.
+pub fn assert_engine_conserves_value<const N: usize, const K: usize>() {
+    crate::sequence::assert_invariant_over_fallible_sequences::<K, _, EngineOp, _, _, _, _>(
+        any_solvent_engine::<N>,
+        Engine::apply,
+        Engine::is_solvent,
+    );
+}
+
+/// Proves that no operation reduces the capital of an account other than the
+/// one it debits, over every sequence of up to `K` operations.
+///
+/// Needs `#[kani::unwind]` of at least `max(N, K) + 1`.
+pub fn assert_engine_protects_principal<const N: usize, const K: usize>() {
+    crate::sequence::assert_transition_over_sequences::<K, _, EngineOp, _, _, _, _>(
+        any_solvent_engine::<N>,
+        Engine::apply,
+        |before: &Engine<N>, op, after| {
+            let debited = op.debited_account::<N>();
+            let mut i = 0;
+            while i < N {
+                if Some(i) != debited && after.accounts[i].capital < before.accounts[i].capital {
+                    return false;
+                }
+                i += 1;
+            }
+            true
+        },
+    );
+}
+
+#[cfg(kani)]
+mod proofs {
+    use super::*;
+
+    #[kani::proof]
+    #[kani::unwind(4)]
+    fn proof_engine_conserves_value() {
+        assert_engine_conserves_value::<2, 3>();
+    }
+
+    #[kani::proof]
+    #[kani::unwind(4)]
+    fn proof_engine_protects_principal() {
+        assert_engine_protects_principal::<2, 3>();
+    }
+
+    #[kani::proof]
+    #[kani::unwind(3)]
+    fn proof_only_underwater_accounts_are_liquidated() {
+        let mut engine = any_solvent_engine::<2>();
+        let i: usize = kani::any();
+        kani::assume(i < 2);
+        let equity = engine.equity(i).unwrap();
+        let liquidated = engine.liquidate(i).is_ok();
+        assert!(liquidated == (equity < 0));
+        crate::cover!(
+            !liquidated && engine.accounts[i].pnl < 0,
+            "losing account with enough capital is kept"
+        );
+    }
+
+    crate::__tier_full! {
+        #[kani::proof]
+        #[kani::unwind(5)]
+        fn proof_engine_conserves_value_deep() {
+            assert_engine_conserves_value::<3, 4>();
+        }
+    }
+}
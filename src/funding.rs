@@ -0,0 +1,197 @@
+//! Funding rate derivation and cumulative funding indices.
+//!
+//! [`funding_rate`] turns the mark/index premium plus an interest component
+//! into a clamped per-interval rate. Each crank folds that rate into a
+//! [`FundingIndex`] holding the cumulative funding per unit of size for each
+//! side, and accounts settle from the index delta since their last snapshot.
+//!
+//! Payers round up and receivers round down at every step, so settled
+//! payments never create value; the shortfall is at most one unit per account
+//! while open interest stays below [`INDEX_SCALE`].
+//!
+//! Like [`risk`](crate::risk), these are plain `core` integer functions and
+//! are compiled into on-chain builds with the `risk` feature.
+
+/// Denominator of funding rates: a rate of `1_000_000` is 100% per interval.
+///
+/// The same denominator can be passed to
+/// [`funding_payment`](crate::risk::funding_payment) as `rate_den`.
+pub const RATE_DENOMINATOR: u128 = 1_000_000;
+
+/// Fixed-point scale of [`FundingIndex`] values.
+pub const INDEX_SCALE: u128 = 1_000_000_000;
+
+/// Funding rate for one interval: `(mark - index) / index + interest_rate`,
+/// clamped to `[-max_rate, max_rate]`.
+///
+/// Rates are in units of [`RATE_DENOMINATOR`]; positive means longs pay
+/// shorts. The premium rounds toward zero and is 0 when `index_price == 0`.
+#[must_use]
+pub fn funding_rate(
+    mark_price: u128,
+    index_price: u128,
+    interest_rate: i128,
+    max_rate: u128,
+) -> i128 {
+    let premium = if index_price == 0 {
+        0
+    } else {
+        let (diff, above) = if mark_price >= index_price {
+            (mark_price - index_price, true)
+        } else {
+            (index_price - mark_price, false)
+        };
+        let q = diff / index_price;
+        let r = diff % index_price;
+        let tail = match r.checked_mul(RATE_DENOMINATOR) {
+            Some(x) => x / index_price,
+            None => r / index_price.div_ceil(RATE_DENOMINATOR),
+        };
+        let magnitude = q
+            .saturating_mul(RATE_DENOMINATOR)
+            .saturating_add(tail)
+            .min(i128::MAX as u128) as i128;
+        if above {
+            magnitude
+        } else {
+            -magnitude
+        }
+    };
+    let max = max_rate.min(i128::MAX as u128) as i128;
+    premium.saturating_add(interest_rate).clamp(-max, max)
+}
+
+/// Cumulative funding paid per unit of size by each side, scaled by
+/// [`INDEX_SCALE`]. Negative values mean the side has received funding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FundingIndex {
+    /// Cumulative funding per unit of long size.
+    pub long: i128,
+    /// Cumulative funding per unit of short size.
+    pub short: i128,
+}
+
+impl FundingIndex {
+    /// Index after one crank at `rate` and `price` with the given open
+    /// interest on each side.
+    ///
+    /// The paying side is charged `rate * price` per unit, rounded up; the
+    /// receiving side is credited the same total spread over its open
+    /// interest, rounded down. Nothing moves while either side is empty.
+    /// Returns `None` on overflow.
+    #[must_use]
+    pub fn crank(self, rate: i128, price: u128, long_oi: u128, short_oi: u128) -> Option<Self> {
+        if rate == 0 || long_oi == 0 || short_oi == 0 {
+            return Some(self);
+        }
+        let (payer_oi, receiver_oi) = if rate > 0 {
+            (long_oi, short_oi)
+        } else {
+            (short_oi, long_oi)
+        };
+        let per_payer = rate
+            .unsigned_abs()
+            .checked_mul(price)?
+            .checked_mul(INDEX_SCALE)?
+            .div_ceil(RATE_DENOMINATOR);
+        let per_receiver = per_payer.checked_mul(payer_oi)? / receiver_oi;
+        let paid = i128::try_from(per_payer).ok()?;
+        let received = i128::try_from(per_receiver).ok()?;
+
+        let mut next = self;
+        if rate > 0 {
+            next.long = next.long.checked_add(paid)?;
+            next.short = next.short.checked_sub(received)?;
+        } else {
+            next.short = next.short.checked_add(paid)?;
+            next.long = next.long.checked_sub(received)?;
+        }
+        Some(next)
+    }
+
+    /// Funding owed by a position of `size` (positive long, negative short)
+    /// since `snapshot` was taken. Positive = account pays.
+    ///
+    /// Payments round up and receipts round down. Saturates at the `i128`
+    /// range.
+    #[must_use]
+    pub fn settle(&self, size: i128, snapshot: &FundingIndex) -> i128 {
+        let delta = if size >= 0 {
+            self.long.saturating_sub(snapshot.long)
+        } else {
+            self.short.saturating_sub(snapshot.short)
+        };
+        let amount = size.unsigned_abs().saturating_mul(delta.unsigned_abs());
+        if delta >= 0 {
+            amount.div_ceil(INDEX_SCALE).min(i128::MAX as u128) as i128
+        } else {
+            -((amount / INDEX_SCALE) as i128)
+        }
+    }
+}
+
+#[cfg(kani)]
+mod proofs {
+    use super::*;
+    use crate::tier::{any_signed, any_unsigned};
+
+    crate::tiered_proof! {
+        fn proof_funding_rate_is_clamped<W>() {
+            let mark: u128 = any_unsigned::<W>();
+            let index: u128 = any_unsigned::<W>();
+            let interest: i128 = any_signed::<W>();
+            let max_rate: u128 = any_unsigned::<W>();
+
+            let rate = funding_rate(mark, index, interest, max_rate);
+            assert!(rate.unsigned_abs() <= max_rate);
+            if interest == 0 {
+                // Without interest the rate follows the premium's sign.
+                assert!(mark >= index || rate <= 0);
+                assert!(mark <= index || rate >= 0);
+            }
+        }
+    }
+
+    const N: usize = 3;
+
+    crate::tiered_proof! {
+        #[cfg_attr(kani, kani::unwind(4))]
+        fn proof_funding_payments_net_to_zero<W>() {
+            let start = FundingIndex {
+                long: any_signed::<W>(),
+                short: any_signed::<W>(),
+            };
+            let rate: i128 = any_signed::<W>();
+            let price: u128 = any_unsigned::<W>();
+
+            let mut sizes = [0i128; N];
+            let (mut long_oi, mut short_oi) = (0u128, 0u128);
+            let mut i = 0;
+            while i < N {
+                sizes[i] = any_signed::<W>();
+                if sizes[i] > 0 {
+                    long_oi = long_oi.saturating_add(sizes[i].unsigned_abs());
+                } else {
+                    short_oi = short_oi.saturating_add(sizes[i].unsigned_abs());
+                }
+                i += 1;
+            }
+            kani::assume(long_oi < INDEX_SCALE && short_oi < INDEX_SCALE);
+
+            if let Some(end) = start.crank(rate, price, long_oi, short_oi) {
+                let mut net: i128 = 0;
+                let mut i = 0;
+                while i < N {
+                    let owed = end.settle(sizes[i], &start);
+                    // The side named by the rate pays, the other receives.
+                    let pays = (sizes[i] > 0) == (rate > 0);
+                    assert!(owed == 0 || (owed > 0) == pays);
+                    net += owed;
+                    i += 1;
+                }
+                assert!(net >= 0 && net <= N as i128);
+                crate::cover!(net == 0 && rate != 0 && long_oi > 0 && short_oi > 0, "funding nets exactly");
+            }
+        }
+    }
+}
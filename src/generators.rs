@@ -1,38 +1,30 @@
 //! Constrained symbolic generators for common domains.
 
+use crate::backend;
+
 /// Arbitrary `u8` in `[0, max]` inclusive.
 pub fn any_u8_up_to(max: u8) -> u8 {
-    let v: u8 = kani::any();
-    kani::assume(v <= max);
-    v
+    backend::any_in_range(0, max)
 }
 
 /// Arbitrary `u16` in `[min, max]` inclusive.
 pub fn any_u16_range(min: u16, max: u16) -> u16 {
-    let v: u16 = kani::any();
-    kani::assume(v >= min && v <= max);
-    v
+    backend::any_in_range(min, max)
 }
 
 /// Arbitrary `u64` in `[min, max]` inclusive.
 pub fn any_u64_range(min: u64, max: u64) -> u64 {
-    let v: u64 = kani::any();
-    kani::assume(v >= min && v <= max);
-    v
+    backend::any_in_range(min, max)
 }
 
 /// Arbitrary `i64` in `[min, max]` inclusive.
 pub fn any_i64_range(min: i64, max: i64) -> i64 {
-    let v: i64 = kani::any();
-    kani::assume(v >= min && v <= max);
-    v
+    backend::any_in_range(min, max)
 }
 
 /// Arbitrary `u128` in `[0, max]` inclusive.
 pub fn any_u128_up_to(max: u128) -> u128 {
-    let v: u128 = kani::any();
-    kani::assume(v <= max);
-    v
+    backend::any_in_range(0, max)
 }
 
 /// Quality/percentage score in `[0, 100]`.
@@ -57,13 +49,13 @@ pub fn any_bps() -> u64 {
 
 /// Arbitrary timestamp (full `i64` range).
 pub fn any_timestamp() -> i64 {
-    kani::any()
+    backend::any()
 }
 
 /// Pair of timestamps where `t1 <= t2`.
 pub fn any_ordered_timestamps() -> (i64, i64) {
-    let t1: i64 = kani::any();
-    let t2: i64 = kani::any();
-    kani::assume(t1 <= t2);
+    let t1: i64 = backend::any();
+    let t2: i64 = backend::any();
+    backend::assume(t1 <= t2);
     (t1, t2)
 }
@@ -1,22 +1,53 @@
 //! Reusable Kani formal verification harnesses for Solana program math.
 //!
 //! This crate is intentionally a no-op in normal builds. Everything is gated
-//! behind `cfg(kani)`.
+//! behind `cfg(kani)`, or the `runtime` feature which runs the same helpers
+//! against randomized inputs under plain `cargo test`.
+//!
+//! The `risk` feature additionally compiles the [`risk`], [`adl`] and
+//! [`funding`] primitives into ordinary `no_std` builds (e.g. BPF/SBF
+//! programs), so the deployed functions are the same ones the proofs cover.
+
+#![cfg_attr(not(any(kani, feature = "runtime")), no_std)]
+
+#[cfg(any(kani, feature = "runtime"))]
+pub mod backend;
 
-#[cfg(kani)]
+#[cfg(all(not(kani), feature = "runtime"))]
+pub mod runtime;
+
+#[cfg(any(kani, feature = "runtime"))]
 pub mod generators;
 
-#[cfg(kani)]
+#[cfg(any(kani, feature = "runtime"))]
 pub mod token;
 
-#[cfg(kani)]
+#[cfg(any(kani, feature = "runtime"))]
 pub mod staking;
 
-#[cfg(kani)]
+#[cfg(any(kani, feature = "runtime"))]
 pub mod bounds;
 
-#[cfg(kani)]
+#[cfg(any(kani, feature = "runtime"))]
 pub mod math;
 
-#[cfg(kani)]
+#[cfg(any(kani, feature = "runtime", feature = "risk"))]
 pub mod risk;
+
+#[cfg(any(kani, feature = "runtime", feature = "risk"))]
+pub mod adl;
+
+#[cfg(any(kani, feature = "runtime", feature = "risk"))]
+pub mod funding;
+
+#[cfg(any(kani, feature = "runtime"))]
+pub mod amm;
+
+#[cfg(any(kani, feature = "runtime"))]
+pub mod sequence;
+
+#[cfg(any(kani, feature = "runtime"))]
+pub mod engine;
+
+#[cfg(any(kani, feature = "runtime"))]
+pub mod tier;
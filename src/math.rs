@@ -1,5 +1,7 @@
 //! Proof helpers for math specs and monotonicity.
 
+use crate::backend::{self, Arbitrary};
+
 /// Reference implementation of a capped ceiling weighted average.
 pub fn ceiling_weighted_average(pairs: &[(u8, u16)], cap: u8) -> u8 {
     let mut weighted_sum: u128 = 0;
@@ -14,7 +16,7 @@ pub fn ceiling_weighted_average(pairs: &[(u8, u16)], cap: u8) -> u8 {
     if total_weight == 0 {
         return 0;
     }
-    let consensus = (weighted_sum + total_weight - 1) / total_weight;
+    let consensus = weighted_sum.div_ceil(total_weight);
     consensus.min(cap as u128) as u8
 }
 
@@ -49,7 +51,7 @@ where
     F: Fn(u8) -> u8,
     S: Fn(u8) -> u8,
 {
-    let input: u8 = kani::any();
+    let input: u8 = backend::any();
     assert_eq!(
         actual_fn(input),
         spec_fn(input),
@@ -57,6 +59,87 @@ where
     );
 }
 
+/// Assert that two implementations agree on every input.
+///
+/// For refactors and optimized rewrites, e.g. replacing `u128` intermediate
+/// math with a `u64` formulation. Multi-argument functions take a tuple input.
+pub fn assert_equivalent<I, O, F, G>(reference_fn: F, optimized_fn: G)
+where
+    I: Arbitrary,
+    O: PartialEq,
+    F: Fn(&I) -> O,
+    G: Fn(&I) -> O,
+{
+    assert_equivalent_when(reference_fn, optimized_fn, |_| true);
+}
+
+/// [`assert_equivalent`] restricted to inputs satisfying `precondition`.
+pub fn assert_equivalent_when<I, O, F, G, P>(reference_fn: F, optimized_fn: G, precondition: P)
+where
+    I: Arbitrary,
+    O: PartialEq,
+    F: Fn(&I) -> O,
+    G: Fn(&I) -> O,
+    P: Fn(&I) -> bool,
+{
+    let input: I = backend::any();
+    backend::assume(precondition(&input));
+    assert!(
+        reference_fn(&input) == optimized_fn(&input),
+        "implementations are not equivalent"
+    );
+}
+
+/// [`assert_equivalent_when`] for `Option`-returning functions: both must
+/// return `None` together, and equal values otherwise.
+pub fn assert_equivalent_option_when<I, T, F, G, P>(
+    reference_fn: F,
+    optimized_fn: G,
+    precondition: P,
+) where
+    I: Arbitrary,
+    T: PartialEq,
+    F: Fn(&I) -> Option<T>,
+    G: Fn(&I) -> Option<T>,
+    P: Fn(&I) -> bool,
+{
+    let input: I = backend::any();
+    backend::assume(precondition(&input));
+    match (reference_fn(&input), optimized_fn(&input)) {
+        (Some(a), Some(b)) => assert!(a == b, "implementations are not equivalent"),
+        (None, None) => {}
+        _ => panic!("implementations disagree on None"),
+    }
+}
+
+/// [`assert_equivalent_when`] for `Result`-returning functions: both must
+/// succeed together with equal values, or fail with the same error variant.
+///
+/// Errors are compared by variant only, so the error type does not need
+/// `PartialEq` and error payloads (messages, offsets) may differ.
+pub fn assert_equivalent_result_when<I, T, E, F, G, P>(
+    reference_fn: F,
+    optimized_fn: G,
+    precondition: P,
+) where
+    I: Arbitrary,
+    T: PartialEq,
+    F: Fn(&I) -> Result<T, E>,
+    G: Fn(&I) -> Result<T, E>,
+    P: Fn(&I) -> bool,
+{
+    let input: I = backend::any();
+    backend::assume(precondition(&input));
+    match (reference_fn(&input), optimized_fn(&input)) {
+        (Ok(a), Ok(b)) => assert!(a == b, "implementations are not equivalent"),
+        (Err(a), Err(b)) => assert!(
+            core::mem::discriminant(&a) == core::mem::discriminant(&b),
+            "implementations return different error variants"
+        ),
+        _ => panic!("implementations disagree on Ok vs Err"),
+    }
+}
+
 /// Assert monotonicity of an `i64 -> u64` function.
 ///
 /// For all symbolic `x1 <= x2`: `f(x1) <= f(x2)`.
@@ -64,9 +147,9 @@ pub fn assert_monotonic_i64_to_u64<F>(f: F)
 where
     F: Fn(i64) -> u64,
 {
-    let x1: i64 = kani::any();
-    let x2: i64 = kani::any();
-    kani::assume(x1 <= x2);
+    let x1: i64 = backend::any();
+    let x2: i64 = backend::any();
+    backend::assume(x1 <= x2);
     assert!(
         f(x1) <= f(x2),
         "function is not monotonically non-decreasing"
@@ -78,9 +161,9 @@ pub fn assert_monotonic_u64<F>(f: F)
 where
     F: Fn(u64) -> u64,
 {
-    let x1: u64 = kani::any();
-    let x2: u64 = kani::any();
-    kani::assume(x1 <= x2);
+    let x1: u64 = backend::any();
+    let x2: u64 = backend::any();
+    backend::assume(x1 <= x2);
     assert!(
         f(x1) <= f(x2),
         "function is not monotonically non-decreasing"
@@ -95,9 +178,9 @@ pub fn assert_anti_monotonic_u8<F>(f: F)
 where
     F: Fn(u8) -> u8,
 {
-    let x1: u8 = kani::any();
-    let x2: u8 = kani::any();
-    kani::assume(x1 <= x2);
+    let x1: u8 = backend::any();
+    let x2: u8 = backend::any();
+    backend::assume(x1 <= x2);
     assert!(
         f(x1) >= f(x2),
         "function is not monotonically non-increasing"
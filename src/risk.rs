@@ -1,11 +1,43 @@
 //! Percolator-style risk primitives and Kani proofs.
 //!
 //! Reference: https://github.com/aeyakovenko/percolator
+//!
+//! The functions here are plain `core` integer math. Enable the `risk` feature
+//! to call them from on-chain code; the `proofs` module stays `cfg(kani)`.
+//!
+//! Helpers that saturate or fall back to a default have `checked_*` variants
+//! returning [`RiskError`] instead; both agree whenever the checked one is `Ok`.
+//!
+//! With the `kani-contracts` feature each primitive carries a Kani function
+//! contract (`requires`/`ensures`) checked by a `proof_for_contract` harness.
+//! Downstream proofs can then replace a primitive by its contract instead of
+//! re-exploring its body:
+//!
+//! ```ignore
+//! #[kani::proof]
+//! #[kani::stub_verified(kani_solana::risk::effective_pnl)]
+//! fn engine_payout_is_bounded() { /* ... */ }
+//! ```
+//!
+//! Run with `cargo kani --features kani-contracts -Z function-contracts`.
+//! The contract harnesses cover the full `u128`/`i128` domain, so a stubbed
+//! contract holds for every input a caller can pass.
 
 /// Global haircut ratio `h` as used in Percolator.
 ///
 /// Returns `(h_num, h_den)` where `h = h_num / h_den`.
 /// When `pnl_pos_total == 0` there are no profitable accounts, so `h = 1`.
+#[cfg_attr(
+    all(kani, feature = "kani-contracts"),
+    kani::ensures(|result: &(u128, u128)| {
+        let (num, den) = *result;
+        den != 0
+            && num <= den
+            && (pnl_pos_total == 0 || den == pnl_pos_total)
+            && (pnl_pos_total == 0
+                || num <= vault.saturating_sub(principal_total.saturating_add(insurance)))
+    })
+)]
 #[must_use]
 pub fn haircut_ratio(
     vault: u128,
@@ -30,6 +62,14 @@ pub fn haircut_ratio(
 /// - Callers should pass `h_den > 0` and `h_num <= h_den` (as produced by `haircut_ratio`).
 /// - If `h_den == 0`, this returns 0 to avoid division-by-zero.
 /// - If `h_num > h_den`, it is clamped to `h_den`.
+#[cfg_attr(
+    all(kani, feature = "kani-contracts"),
+    kani::requires(h_den > 0 && h_num <= h_den)
+)]
+#[cfg_attr(
+    all(kani, feature = "kani-contracts"),
+    kani::ensures(|result: &u128| *result <= pnl_i.max(0) as u128)
+)]
 #[must_use]
 pub fn effective_pnl(pnl_i: i128, h_num: u128, h_den: u128) -> u128 {
     let pos = pnl_i.max(0) as u128;
@@ -58,6 +98,12 @@ pub fn effective_pnl(pnl_i: i128, h_num: u128, h_den: u128) -> u128 {
 /// Returns the fraction of `gross_profit` that is "warmed" (eligible for withdrawal),
 /// as `warmed = gross_profit * elapsed / warmup_period` (floored).
 /// Clamped so `elapsed >= warmup_period` yields the full amount.
+#[cfg_attr(
+    all(kani, feature = "kani-contracts"),
+    kani::ensures(|result: &u128| {
+        *result <= gross_profit && (elapsed < warmup_period || *result == gross_profit)
+    })
+)]
 #[must_use]
 pub fn warmup_slope(gross_profit: u128, elapsed: u64, warmup_period: u64) -> u128 {
     if warmup_period == 0 || elapsed >= warmup_period {
@@ -71,10 +117,103 @@ pub fn warmup_slope(gross_profit: u128, elapsed: u64, warmup_period: u64) -> u12
     }
 }
 
+/// Per-account warmup ledger built on [`warmup_slope`].
+///
+/// Profit warms linearly over `warmup_period` slots. New profit restarts the
+/// clock for everything not yet warmed, while profit that already warmed
+/// stays withdrawable in `vested`. Warmup is frozen while a haircut is active.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WarmupState {
+    /// Slots for profit to warm fully.
+    pub warmup_period: u64,
+    /// Profit warmed in earlier epochs and not yet withdrawn.
+    pub vested: u128,
+    /// Profit warming in the current epoch.
+    pub gross_profit: u128,
+    /// Slots accrued in the current epoch.
+    pub elapsed: u64,
+    /// Amount withdrawn from the current epoch's warmed profit.
+    pub withdrawn: u128,
+}
+
+impl WarmupState {
+    /// Empty ledger with the given warmup period.
+    pub const fn new(warmup_period: u64) -> Self {
+        Self {
+            warmup_period,
+            vested: 0,
+            gross_profit: 0,
+            elapsed: 0,
+            withdrawn: 0,
+        }
+    }
+
+    /// Profit warmed in the current epoch, including what was withdrawn.
+    #[must_use]
+    pub fn warmed(&self) -> u128 {
+        warmup_slope(self.gross_profit, self.elapsed, self.warmup_period)
+    }
+
+    /// Profit that can be withdrawn now.
+    #[must_use]
+    pub fn withdrawable(&self) -> u128 {
+        self.vested
+            .saturating_add(self.warmed().saturating_sub(self.withdrawn))
+    }
+
+    /// Advance the clock by `slots` unless the haircut `h_num / h_den` is
+    /// active (`h_num < h_den`), in which case warmup is frozen.
+    pub fn accrue(&mut self, slots: u64, h_num: u128, h_den: u128) {
+        if h_num >= h_den {
+            self.elapsed = self.elapsed.saturating_add(slots);
+        }
+    }
+
+    /// Withdraw up to `amount` of warmed profit, vested first. Returns the
+    /// amount withdrawn.
+    #[must_use]
+    pub fn withdraw(&mut self, amount: u128) -> u128 {
+        let amount = amount.min(self.withdrawable());
+        let from_vested = amount.min(self.vested);
+        self.vested -= from_vested;
+        self.withdrawn += amount - from_vested;
+        amount
+    }
+
+    /// Credit new `profit` and restart the warmup clock: warmed profit not
+    /// yet withdrawn moves to `vested`, the unwarmed rest warms again from
+    /// zero together with `profit`. Leaves the state unchanged on overflow.
+    pub fn on_new_profit(&mut self, profit: u128) -> Result<(), RiskError> {
+        let warmed = self.warmed();
+        let vested = self
+            .vested
+            .checked_add(warmed.saturating_sub(self.withdrawn))
+            .ok_or(RiskError::Overflow)?;
+        let gross_profit = (self.gross_profit - warmed)
+            .checked_add(profit)
+            .ok_or(RiskError::Overflow)?;
+        *self = Self {
+            vested,
+            gross_profit,
+            elapsed: 0,
+            withdrawn: 0,
+            ..*self
+        };
+        Ok(())
+    }
+}
+
 /// Fee-debt sweep: given an account's accumulated fee debt and available balance,
 /// computes how much is swept (paid) and the remaining debt.
 ///
 /// Returns `(swept, remaining_debt)`.
+#[cfg_attr(
+    all(kani, feature = "kani-contracts"),
+    kani::ensures(|result: &(u128, u128)| {
+        let (swept, remaining) = *result;
+        swept <= available && swept <= fee_debt && remaining == fee_debt - swept
+    })
+)]
 #[must_use]
 pub fn fee_debt_sweep(fee_debt: u128, available: u128) -> (u128, u128) {
     let swept = fee_debt.min(available);
@@ -86,6 +225,18 @@ pub fn fee_debt_sweep(fee_debt: u128, available: u128) -> (u128, u128) {
 ///
 /// Positive return = account pays; negative = account receives.
 /// Uses integer math: `payment = position * rate_num / rate_den`.
+#[cfg_attr(
+    all(kani, feature = "kani-contracts"),
+    kani::ensures(|result: &i128| {
+        let zero_input = position == 0 || rate_num == 0 || rate_den == 0;
+        let pays = (rate_num > 0) == is_long;
+        (!zero_input || *result == 0)
+            && (*result == 0 || (*result > 0) == pays)
+            && (rate_num.unsigned_abs() > rate_den
+                || position.checked_mul(rate_num.unsigned_abs()).is_none()
+                || result.unsigned_abs() <= position)
+    })
+)]
 #[must_use]
 pub fn funding_payment(position: u128, rate_num: i128, rate_den: u128, is_long: bool) -> i128 {
     if position == 0 || rate_num == 0 || rate_den == 0 {
@@ -118,301 +269,1547 @@ pub fn funding_payment(position: u128, rate_num: i128, rate_den: u128, is_long:
 /// and updated insurance fund.
 ///
 /// Returns `(writeoff, new_insurance)` where writeoff is capped by insurance.
+#[cfg_attr(
+    all(kani, feature = "kani-contracts"),
+    kani::ensures(|result: &(u128, u128)| {
+        let (writeoff, new_insurance) = *result;
+        writeoff <= negative_equity
+            && writeoff <= insurance
+            && new_insurance == insurance - writeoff
+    })
+)]
 #[must_use]
 pub fn loss_writeoff(negative_equity: u128, insurance: u128) -> (u128, u128) {
     let writeoff = negative_equity.min(insurance);
     (writeoff, insurance - writeoff)
 }
 
+/// Outcome of [`socialize_loss`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LossSettlement {
+    /// Part of the loss paid by the insurance fund.
+    pub covered: u128,
+    /// Part of the loss taken from positive PnL.
+    pub socialized: u128,
+    /// Part left over once insurance and positive PnL are both exhausted.
+    pub unabsorbed: u128,
+    /// Insurance fund after the settlement.
+    pub insurance: u128,
+    /// Total positive PnL once `socialized` has been taken from the accounts.
+    pub pnl_pos_total: u128,
+}
+
+/// Loss writeoff that socializes what insurance cannot cover.
+///
+/// Extends [`loss_writeoff`]: insurance pays first, the remainder is
+/// `socialized` against positive PnL, and only a loss larger than both is
+/// reported as `unabsorbed`.
+///
+/// The socialized amount must be taken out of the accounts' PnL, e.g. with
+/// [`socialize_pro_rata`], before the haircut is recomputed. Lowering
+/// `pnl_pos_total` alone shrinks the haircut denominator while every
+/// account's PnL stays the same, which raises payouts above the residual.
+#[cfg_attr(
+    all(kani, feature = "kani-contracts"),
+    kani::ensures(|result: &LossSettlement| {
+        result.covered + result.socialized + result.unabsorbed == negative_equity
+            && result.insurance == insurance - result.covered
+            && result.pnl_pos_total == pnl_pos_total - result.socialized
+            && (result.unabsorbed == 0 || (result.insurance == 0 && result.pnl_pos_total == 0))
+    })
+)]
+#[must_use]
+pub fn socialize_loss(
+    negative_equity: u128,
+    insurance: u128,
+    pnl_pos_total: u128,
+) -> LossSettlement {
+    let (covered, insurance) = loss_writeoff(negative_equity, insurance);
+    let rest = negative_equity - covered;
+    let socialized = rest.min(pnl_pos_total);
+    LossSettlement {
+        covered,
+        socialized,
+        unabsorbed: rest - socialized,
+        insurance,
+        pnl_pos_total: pnl_pos_total - socialized,
+    }
+}
+
+/// Split `socialized` across the positive entries of `pnl` pro rata.
+///
+/// Account `i` gives `floor(socialized * pnl_i / pnl_pos_total)`; the rounding
+/// remainder is taken in index order from accounts with profit left, so the
+/// amounts sum to `min(socialized, pnl_pos_total)` and none exceeds the
+/// account's positive PnL. Subtract each amount from the account's PnL.
+#[must_use]
+pub fn socialize_pro_rata<const N: usize>(pnl: &[i128; N], socialized: u128) -> [u128; N] {
+    let mut total: u128 = 0;
+    let mut i = 0;
+    while i < N {
+        total = total.saturating_add(pnl[i].max(0) as u128);
+        i += 1;
+    }
+    let mut taken = [0u128; N];
+    if total == 0 {
+        return taken;
+    }
+    let socialized = socialized.min(total);
+
+    let mut left = socialized;
+    let mut i = 0;
+    while i < N {
+        // `socialized / total` is a ratio at most 1, so this is the haircut
+        // formula; a tail it drops on overflow goes to the remainder pass.
+        taken[i] = effective_pnl(pnl[i], socialized, total).min(left);
+        left -= taken[i];
+        i += 1;
+    }
+    let mut i = 0;
+    while i < N && left > 0 {
+        let extra = (pnl[i].max(0) as u128 - taken[i]).min(left);
+        taken[i] += extra;
+        left -= extra;
+        i += 1;
+    }
+    taken
+}
+
+/// Basis-point denominator used by margin requirements and ratios.
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Signed PnL of a position of `size` (positive long, negative short) opened
+/// at `entry_price` and marked at `mark_price`: `size * (mark - entry)`.
+///
+/// Saturates at the `i128` range.
+#[must_use]
+pub fn unrealized_pnl(size: i128, entry_price: u128, mark_price: u128) -> i128 {
+    let (move_abs, price_up) = if mark_price >= entry_price {
+        (mark_price - entry_price, true)
+    } else {
+        (entry_price - mark_price, false)
+    };
+    let magnitude = size.unsigned_abs().saturating_mul(move_abs);
+    let gains = (size >= 0) == price_up;
+    if gains {
+        magnitude.min(i128::MAX as u128) as i128
+    } else if magnitude > i128::MAX as u128 {
+        i128::MIN
+    } else {
+        -(magnitude as i128)
+    }
+}
+
+/// Notional value `|size| * price`, saturating at `u128::MAX`.
+#[must_use]
+pub fn position_notional(size: i128, price: u128) -> u128 {
+    size.unsigned_abs().saturating_mul(price)
+}
+
+/// Margin requirement `ceil(notional * margin_bps / 10_000)`.
+///
+/// Used for both initial and maintenance margin with the respective ratio.
+/// Rounds up, so the requirement is never understated; saturates at
+/// `u128::MAX`.
+#[cfg_attr(
+    all(kani, feature = "kani-contracts"),
+    kani::ensures(|result: &u128| notional != 0 || *result == 0)
+)]
+#[must_use]
+pub fn margin_requirement(notional: u128, margin_bps: u64) -> u128 {
+    bps_ceil(notional, margin_bps)
+}
+
+/// Account equity: `capital + effective positive PnL + negative PnL - fee_debt`.
+///
+/// Positive PnL is scaled by the haircut `h_num / h_den` (see
+/// [`effective_pnl`]); losses count in full. Saturates at the `i128` range.
+#[must_use]
+pub fn account_equity(capital: u128, pnl: i128, h_num: u128, h_den: u128, fee_debt: u128) -> i128 {
+    let capital = capital.min(i128::MAX as u128) as i128;
+    let profit = effective_pnl(pnl, h_num, h_den).min(i128::MAX as u128) as i128;
+    let fee_debt = fee_debt.min(i128::MAX as u128) as i128;
+    capital
+        .saturating_add(profit)
+        .saturating_add(pnl.min(0))
+        .saturating_sub(fee_debt)
+}
+
+/// Whether `equity` falls short of the maintenance requirement on `notional`.
+///
+/// Negative equity is always liquidatable.
+#[must_use]
+pub fn is_liquidatable(equity: i128, notional: u128, maintenance_bps: u64) -> bool {
+    equity < 0 || (equity as u128) < margin_requirement(notional, maintenance_bps)
+}
+
+/// Whether `equity` covers the initial requirement on `notional`, i.e. the
+/// position may be opened or increased.
+#[must_use]
+pub fn meets_initial_margin(equity: i128, notional: u128, initial_bps: u64) -> bool {
+    equity >= 0 && (equity as u128) >= margin_requirement(notional, initial_bps)
+}
+
+/// Margin ratio `equity / notional` in basis points, rounded down.
+///
+/// Returns `u128::MAX` for a flat position (`notional == 0`) and 0 for
+/// non-positive equity, so callers never divide by zero. Exact unless
+/// `notional` exceeds `2^114`, where the fractional part is underestimated.
+#[cfg_attr(
+    all(kani, feature = "kani-contracts"),
+    kani::ensures(|result: &u128| {
+        (notional != 0 || *result == u128::MAX) && (notional == 0 || equity > 0 || *result == 0)
+    })
+)]
+#[must_use]
+pub fn margin_ratio_bps(equity: i128, notional: u128) -> u128 {
+    if notional == 0 {
+        return u128::MAX;
+    }
+    if equity <= 0 {
+        return 0;
+    }
+    let den = BPS_DENOMINATOR as u128;
+    let e = equity as u128;
+    let q = e / notional;
+    let r = e % notional;
+    let tail = match r.checked_mul(den) {
+        Some(x) => x / notional,
+        None => r / notional.div_ceil(den),
+    };
+    q.saturating_mul(den).saturating_add(tail)
+}
+
+/// Price at which a position of `size` opened at `entry_price` with
+/// `capital` becomes liquidatable under `maintenance_bps`.
+///
+/// For a long this is the highest liquidatable price (every price at or below
+/// it is liquidatable), for a short the lowest (every price at or above it).
+/// Agrees exactly with [`is_liquidatable`] on [`account_equity`] without a
+/// haircut or fee debt. Returns `None` for a flat position, a long that stays
+/// healthy down to price 0, `maintenance_bps` above 100%, or when an
+/// intermediate product overflows `u128`.
+///
+/// The price is exact to one unit: one unit in the healthy direction is not
+/// liquidatable. Equity itself moves by `|size|` per unit of price, so at the
+/// returned price it falls short of the maintenance requirement by up to
+/// `|size| + margin_requirement(|size|, maintenance_bps)`, which is within one
+/// unit of the requirement only per unit of size.
+#[must_use]
+pub fn liquidation_price(
+    size: i128,
+    entry_price: u128,
+    capital: u128,
+    maintenance_bps: u64,
+) -> Option<u128> {
+    if size == 0 || maintenance_bps > BPS_DENOMINATOR {
+        return None;
+    }
+    // Liquidatable iff `10_000 * equity < |size| * price * maintenance_bps`,
+    // which is linear in price, so the boundary has a closed form.
+    let den = BPS_DENOMINATOR as u128;
+    let bps = maintenance_bps as u128;
+    let abs = size.unsigned_abs();
+    let cost = abs.checked_mul(entry_price)?;
+    if size > 0 {
+        // `price * |size| * (10_000 - bps) < 10_000 * (cost - capital)`.
+        if cost <= capital {
+            return None;
+        }
+        let num = (cost - capital).checked_mul(den)?;
+        if bps == den {
+            return Some(u128::MAX);
+        }
+        let slope = abs.checked_mul(den - bps)?;
+        Some((num - 1) / slope)
+    } else {
+        // `price * |size| * (10_000 + bps) > 10_000 * (cost + capital)`.
+        let num = cost.checked_add(capital)?.checked_mul(den)?;
+        let slope = abs.checked_mul(den + bps)?;
+        (num / slope).checked_add(1)
+    }
+}
+
+/// Why a `checked_*` risk helper rejected its inputs.
+///
+/// The plain helpers saturate or fall back to a default instead; the checked
+/// variants surface those cases so engine bugs are not masked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiskError {
+    /// A denominator is zero.
+    ZeroDenominator,
+    /// An intermediate or final value does not fit its type.
+    Overflow,
+    /// A ratio numerator exceeds its denominator.
+    InvalidRatio,
+}
+
+/// [`haircut_ratio`] without saturation: fails if
+/// `principal_total + insurance` overflows.
+pub fn checked_haircut_ratio(
+    vault: u128,
+    principal_total: u128,
+    insurance: u128,
+    pnl_pos_total: u128,
+) -> Result<(u128, u128), RiskError> {
+    let owed = principal_total
+        .checked_add(insurance)
+        .ok_or(RiskError::Overflow)?;
+    Ok(haircut_ratio(vault, owed, 0, pnl_pos_total))
+}
+
+/// [`effective_pnl`] that rejects `h_den == 0` and `h_num > h_den` instead of
+/// defaulting, and fails rather than dropping the rounding tail when
+/// `(pnl % h_den) * h_num` overflows.
+pub fn checked_effective_pnl(pnl_i: i128, h_num: u128, h_den: u128) -> Result<u128, RiskError> {
+    if h_den == 0 {
+        return Err(RiskError::ZeroDenominator);
+    }
+    if h_num > h_den {
+        return Err(RiskError::InvalidRatio);
+    }
+    let pos = pnl_i.max(0) as u128;
+    if h_num == h_den {
+        return Ok(pos);
+    }
+    let tail = (pos % h_den)
+        .checked_mul(h_num)
+        .ok_or(RiskError::Overflow)?;
+    Ok((pos / h_den) * h_num + tail / h_den)
+}
+
+/// [`funding_payment`] that rejects `rate_den == 0` and fails instead of
+/// saturating at the `i128` range.
+pub fn checked_funding_payment(
+    position: u128,
+    rate_num: i128,
+    rate_den: u128,
+    is_long: bool,
+) -> Result<i128, RiskError> {
+    if rate_den == 0 {
+        return Err(RiskError::ZeroDenominator);
+    }
+    let prod = position
+        .checked_mul(rate_num.unsigned_abs())
+        .ok_or(RiskError::Overflow)?;
+    let magnitude = i128::try_from(prod / rate_den).map_err(|_| RiskError::Overflow)?;
+    let raw = if rate_num < 0 { -magnitude } else { magnitude };
+    Ok(if is_long { raw } else { -raw })
+}
+
+/// [`unrealized_pnl`] without saturation.
+pub fn checked_unrealized_pnl(
+    size: i128,
+    entry_price: u128,
+    mark_price: u128,
+) -> Result<i128, RiskError> {
+    let move_abs = mark_price.abs_diff(entry_price);
+    let magnitude = size
+        .unsigned_abs()
+        .checked_mul(move_abs)
+        .ok_or(RiskError::Overflow)?;
+    let pnl = if (size >= 0) == (mark_price >= entry_price) {
+        i128::try_from(magnitude).ok()
+    } else {
+        0i128.checked_sub_unsigned(magnitude)
+    };
+    pnl.ok_or(RiskError::Overflow)
+}
+
+/// [`position_notional`] without saturation.
+pub fn checked_position_notional(size: i128, price: u128) -> Result<u128, RiskError> {
+    size.unsigned_abs()
+        .checked_mul(price)
+        .ok_or(RiskError::Overflow)
+}
+
+/// [`margin_requirement`] without saturation.
+pub fn checked_margin_requirement(notional: u128, margin_bps: u64) -> Result<u128, RiskError> {
+    checked_bps_ceil(notional, margin_bps)
+}
+
+/// [`account_equity`] built on the checked helpers: rejects an invalid
+/// haircut and fails instead of saturating.
+pub fn checked_account_equity(
+    capital: u128,
+    pnl: i128,
+    h_num: u128,
+    h_den: u128,
+    fee_debt: u128,
+) -> Result<i128, RiskError> {
+    let to_i128 = |x: u128| i128::try_from(x).map_err(|_| RiskError::Overflow);
+    let profit = to_i128(checked_effective_pnl(pnl, h_num, h_den)?)?;
+    let fee_debt = to_i128(fee_debt)?;
+    to_i128(capital)?
+        .checked_add(profit)
+        .and_then(|x| x.checked_add(pnl.min(0)))
+        .and_then(|x| x.checked_sub(fee_debt))
+        .ok_or(RiskError::Overflow)
+}
+
+/// [`margin_ratio_bps`] that rejects a flat position (`notional == 0`) and
+/// fails instead of underestimating when `notional` exceeds `2^114`.
+pub fn checked_margin_ratio_bps(equity: i128, notional: u128) -> Result<u128, RiskError> {
+    if notional == 0 {
+        return Err(RiskError::ZeroDenominator);
+    }
+    if equity <= 0 {
+        return Ok(0);
+    }
+    let den = BPS_DENOMINATOR as u128;
+    let e = equity as u128;
+    let tail = (e % notional).checked_mul(den).ok_or(RiskError::Overflow)?;
+    (e / notional)
+        .checked_mul(den)
+        .and_then(|head| head.checked_add(tail / notional))
+        .ok_or(RiskError::Overflow)
+}
+
+/// Perpetual position: signed size (positive long, negative short) and
+/// average entry price.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Position {
+    /// Signed size in base units.
+    pub size: i128,
+    /// Size-weighted average entry price; 0 when flat.
+    pub entry_price: u128,
+}
+
+impl Position {
+    /// Unrealized PnL at `mark_price`; see [`unrealized_pnl`].
+    #[must_use]
+    pub fn unrealized_pnl(&self, mark_price: u128) -> i128 {
+        unrealized_pnl(self.size, self.entry_price, mark_price)
+    }
+
+    /// Apply a fill of `delta` (positive buys, negative sells) at `price`.
+    ///
+    /// Returns the new position and the PnL realized by the fill:
+    /// - open or increase: entry becomes the size-weighted average, rounded
+    ///   against the trader (up for longs, down for shorts); nothing is realized.
+    /// - reduce or close: the closed part realizes `closed * (price - entry)`;
+    ///   the entry is unchanged, or reset to 0 when flat.
+    /// - flip: the whole position is closed as above and the remainder opens
+    ///   at `price`.
+    pub fn fill(self, delta: i128, price: u128) -> Result<(Position, i128), RiskError> {
+        let size = self.size.checked_add(delta).ok_or(RiskError::Overflow)?;
+        if delta == 0 {
+            return Ok((self, 0));
+        }
+        if self.size == 0 || (self.size > 0) == (delta > 0) {
+            let (held, added) = (self.size.unsigned_abs(), delta.unsigned_abs());
+            let cost = held
+                .checked_mul(self.entry_price)
+                .and_then(|x| x.checked_add(added.checked_mul(price)?))
+                .ok_or(RiskError::Overflow)?;
+            let entry_price = if size > 0 {
+                cost.div_ceil(size.unsigned_abs())
+            } else {
+                cost / size.unsigned_abs()
+            };
+            return Ok((Position { size, entry_price }, 0));
+        }
+
+        let closed = delta.unsigned_abs().min(self.size.unsigned_abs());
+        let closed_size = if self.size > 0 {
+            closed as i128
+        } else {
+            0i128.saturating_sub_unsigned(closed)
+        };
+        let realized = checked_unrealized_pnl(closed_size, self.entry_price, price)?;
+        let next = if size == 0 {
+            Position::default()
+        } else if (size > 0) == (self.size > 0) {
+            Position { size, ..self }
+        } else {
+            Position {
+                size,
+                entry_price: price,
+            }
+        };
+        Ok((next, realized))
+    }
+}
+
+/// Trading account touched by [`match_fill`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TradeAccount {
+    /// Free collateral fees are swept from.
+    pub capital: u128,
+    /// Quote balance from fills: `-size * price` summed over fills.
+    pub cash: i128,
+    /// Position size, positive long.
+    pub size: i128,
+    /// Fees charged but not yet swept from capital.
+    pub fee_debt: u128,
+}
+
+impl TradeAccount {
+    /// Add `fee` to the debt and sweep what capital covers with
+    /// [`fee_debt_sweep`]. Returns the amount swept.
+    fn charge_fee(&mut self, fee: u128) -> Result<u128, RiskError> {
+        let debt = self.fee_debt.checked_add(fee).ok_or(RiskError::Overflow)?;
+        let (swept, remaining) = fee_debt_sweep(debt, self.capital);
+        self.capital -= swept;
+        self.fee_debt = remaining;
+        Ok(swept)
+    }
+}
+
+/// Fees from one [`match_fill`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FillFees {
+    /// Taker plus maker fee charged on the fill.
+    pub charged: u128,
+    /// Fees swept from capital, including earlier fee debt.
+    pub collected: u128,
+}
+
+/// Match a fill of `size` (positive: taker buys) at `price`.
+///
+/// The taker's position moves by `size` and the maker's by `-size`, and the
+/// notional cash leg moves between them, so open interest stays balanced and
+/// cash nets to zero. Each side is charged its fee in basis points of notional
+/// (rounded up), collected from capital via [`fee_debt_sweep`]; what capital
+/// cannot cover stays as fee debt. Leaves both accounts unchanged on error.
+pub fn match_fill(
+    taker: &mut TradeAccount,
+    maker: &mut TradeAccount,
+    size: i128,
+    price: u128,
+    taker_fee_bps: u64,
+    maker_fee_bps: u64,
+) -> Result<FillFees, RiskError> {
+    let notional = checked_position_notional(size, price)?;
+    let cash = i128::try_from(notional).map_err(|_| RiskError::Overflow)?;
+    let cash = if size >= 0 { cash } else { -cash };
+    let taker_fee = checked_bps_ceil(notional, taker_fee_bps)?;
+    let maker_fee = checked_bps_ceil(notional, maker_fee_bps)?;
+    let charged = taker_fee
+        .checked_add(maker_fee)
+        .ok_or(RiskError::Overflow)?;
+
+    let (mut t, mut m) = (*taker, *maker);
+    t.size = t.size.checked_add(size).ok_or(RiskError::Overflow)?;
+    m.size = m.size.checked_sub(size).ok_or(RiskError::Overflow)?;
+    t.cash = t.cash.checked_sub(cash).ok_or(RiskError::Overflow)?;
+    m.cash = m.cash.checked_add(cash).ok_or(RiskError::Overflow)?;
+    let collected = t
+        .charge_fee(taker_fee)?
+        .checked_add(m.charge_fee(maker_fee)?)
+        .ok_or(RiskError::Overflow)?;
+
+    *taker = t;
+    *maker = m;
+    Ok(FillFees { charged, collected })
+}
+
+/// One row of a [`FeeSchedule`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeTier {
+    /// Trailing volume from which this tier applies.
+    pub min_volume: u128,
+    /// Taker fee in basis points of notional, rounded up.
+    pub taker_bps: u64,
+    /// Maker rebate in basis points of notional, rounded down, paid out of
+    /// the taker fee.
+    pub maker_rebate_bps: u64,
+}
+
+/// Split of one taker fee from [`FeeSchedule::charge`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    /// Fee charged to the taker.
+    pub taker_fee: u128,
+    /// Rebate paid to the maker.
+    pub maker_rebate: u128,
+    /// Cut routed to the taker's referrer.
+    pub referral: u128,
+    /// Remainder kept by the protocol, including all rounding.
+    pub protocol: u128,
+}
+
+/// Volume-tiered trading fees with maker rebates and a referral cut.
+///
+/// `tiers` are ordered by ascending `min_volume`; a trader pays the last tier
+/// whose threshold their volume reaches, or the first tier below all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeSchedule<const T: usize> {
+    /// Tier table, ascending by `min_volume`.
+    pub tiers: [FeeTier; T],
+    /// Referral share of the fee left after the maker rebate, in basis points.
+    pub referral_bps: u64,
+}
+
+impl<const T: usize> FeeSchedule<T> {
+    /// Whether the table is well formed: at least one tier, thresholds
+    /// ascending, taker fees non-increasing with volume, every rebate at most
+    /// its taker fee and all ratios at most 100%.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        if T == 0 || self.referral_bps > BPS_DENOMINATOR {
+            return false;
+        }
+        let mut i = 0;
+        while i < T {
+            let tier = self.tiers[i];
+            if tier.taker_bps > BPS_DENOMINATOR || tier.maker_rebate_bps > tier.taker_bps {
+                return false;
+            }
+            if i > 0 {
+                let prev = self.tiers[i - 1];
+                if tier.min_volume < prev.min_volume || tier.taker_bps > prev.taker_bps {
+                    return false;
+                }
+            }
+            i += 1;
+        }
+        true
+    }
+
+    /// Tier applying at trailing `volume`. Falls back to an all-zero tier for
+    /// an empty table.
+    #[must_use]
+    pub fn tier(&self, volume: u128) -> FeeTier {
+        let mut selected = if T == 0 {
+            FeeTier::default()
+        } else {
+            self.tiers[0]
+        };
+        let mut i = 1;
+        while i < T {
+            if self.tiers[i].min_volume <= volume {
+                selected = self.tiers[i];
+            }
+            i += 1;
+        }
+        selected
+    }
+
+    /// Fees for a taker fill of `notional` at trailing `volume`.
+    ///
+    /// The rebate is capped at the taker fee, the referral cut comes out of
+    /// what remains, and the protocol keeps the rest, so
+    /// `taker_fee == maker_rebate + referral + protocol` exactly.
+    #[must_use]
+    pub fn charge(&self, notional: u128, volume: u128) -> FeeBreakdown {
+        let tier = self.tier(volume);
+        let taker_fee = bps_ceil(notional, tier.taker_bps);
+        let maker_rebate = bps_floor(notional, tier.maker_rebate_bps).min(taker_fee);
+        let net = taker_fee - maker_rebate;
+        let referral = bps_floor(net, self.referral_bps.min(BPS_DENOMINATOR));
+        FeeBreakdown {
+            taker_fee,
+            maker_rebate,
+            referral,
+            protocol: net - referral,
+        }
+    }
+}
+
+/// `floor(amount * bps / 10_000)`, saturating at `u128::MAX`.
+fn bps_floor(amount: u128, bps: u64) -> u128 {
+    let bps = bps as u128;
+    let den = BPS_DENOMINATOR as u128;
+    (amount / den)
+        .saturating_mul(bps)
+        .saturating_add((amount % den) * bps / den)
+}
+
+/// `ceil(amount * bps / 10_000)`, saturating at `u128::MAX`.
+fn bps_ceil(amount: u128, bps: u64) -> u128 {
+    let bps = bps as u128;
+    let den = BPS_DENOMINATOR as u128;
+    (amount / den)
+        .saturating_mul(bps)
+        .saturating_add(((amount % den) * bps).div_ceil(den))
+}
+
+/// `ceil(amount * bps / 10_000)`, failing instead of saturating.
+fn checked_bps_ceil(amount: u128, bps: u64) -> Result<u128, RiskError> {
+    let bps = bps as u128;
+    let den = BPS_DENOMINATOR as u128;
+    (amount / den)
+        .checked_mul(bps)
+        .and_then(|head| head.checked_add(((amount % den) * bps).div_ceil(den)))
+        .ok_or(RiskError::Overflow)
+}
+
 #[cfg(kani)]
 mod proofs {
     use super::*;
+    use crate::generators::any_bps;
+    use crate::tier::{any_signed, any_unsigned};
+
+    crate::tiered_proof! {
+        fn proof_haircut_ratio_basic_properties<W>() {
+            let v: u128 = any_unsigned::<W>();
+            let c: u128 = any_unsigned::<W>();
+            let i: u128 = any_unsigned::<W>();
+            let p: u128 = any_unsigned::<W>();
+
+            let (num, den) = haircut_ratio(v, c, i, p);
+
+            assert!(den != 0);
+            assert!(num <= den);
+            if p == 0 {
+                assert!((num, den) == (1, 1));
+            } else {
+                assert!(den == p);
+                let residual = v.saturating_sub(c.saturating_add(i));
+                assert!(num <= residual);
+            }
+        }
+    }
 
-    #[kani::proof]
-    fn proof_haircut_ratio_basic_properties() {
-        let v: u128 = kani::any::<u64>() as u128;
-        let c: u128 = kani::any::<u64>() as u128;
-        let i: u128 = kani::any::<u64>() as u128;
-        let p: u128 = kani::any::<u64>() as u128;
-
-        let (num, den) = haircut_ratio(v, c, i, p);
+    crate::tiered_proof! {
+        fn proof_haircut_ratio_is_one_when_residual_covers_profit<W>() {
+            let v: u128 = any_unsigned::<W>();
+            let c: u128 = any_unsigned::<W>();
+            let i: u128 = any_unsigned::<W>();
+            let p: u128 = any_unsigned::<W>();
+            kani::assume(p > 0);
 
-        assert!(den != 0);
-        assert!(num <= den);
-        if p == 0 {
-            assert!((num, den) == (1, 1));
-        } else {
-            assert!(den == p);
             let residual = v.saturating_sub(c.saturating_add(i));
-            assert!(num <= residual);
+            kani::assume(residual >= p);
+
+            let (num, den) = haircut_ratio(v, c, i, p);
+            assert!(num == den);
         }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_haircut_ratio_is_one_when_residual_covers_profit() {
-        let v: u128 = kani::any::<u64>() as u128;
-        let c: u128 = kani::any::<u64>() as u128;
-        let i: u128 = kani::any::<u64>() as u128;
-        let p: u128 = kani::any::<u64>() as u128;
-        kani::assume(p > 0);
+    crate::tiered_proof! {
+        fn proof_principal_protection_across_accounts<W>() {
+            let c_tot: u128 = any_unsigned::<W>();
+            let c_i: u128 = any_unsigned::<W>();
+            kani::assume(c_i <= c_tot);
 
-        let residual = v.saturating_sub(c.saturating_add(i));
-        kani::assume(residual >= p);
+            let loss = kani::any::<u128>().min(c_i);
+            let c_i_new = c_i - loss;
+            let c_tot_new = c_tot - loss;
 
-        let (num, den) = haircut_ratio(v, c, i, p);
-        assert!(num == den);
+            assert!(c_tot_new == c_tot - loss);
+            assert!(c_i_new <= c_i);
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_principal_protection_across_accounts() {
-        let c_tot: u128 = kani::any::<u64>() as u128;
-        let c_i: u128 = kani::any::<u64>() as u128;
-        kani::assume(c_i <= c_tot);
+    crate::tiered_proof! {
+        fn proof_profit_conversion_payout_formula<W>() {
+            let x: u128 = any_unsigned::<W>();
+            let h_num: u128 = any_unsigned::<W>();
+            let h_den: u128 = any_unsigned::<W>();
 
-        let loss = kani::any::<u128>().min(c_i);
-        let c_i_new = c_i - loss;
-        let c_tot_new = c_tot - loss;
+            let y = effective_pnl(x as i128, h_num, h_den);
+            assert!(y <= x);
+        }
+    }
 
-        assert!(c_tot_new == c_tot - loss);
-        assert!(c_i_new <= c_i);
+    crate::tiered_proof! {
+        fn proof_effective_pnl_matches_reference<W>() {
+            let pnl: i128 = any_signed::<W>();
+            let h_num: u128 = any_unsigned::<W>();
+            let h_den: u128 = any_unsigned::<W>();
+            kani::assume(h_den > 0);
+            kani::assume(h_num <= h_den);
+
+            let pos = pnl.max(0) as u128;
+            let actual = effective_pnl(pnl, h_num, h_den);
+            if let Some(product) = pos.checked_mul(h_num) {
+                assert!(actual == product / h_den);
+            }
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_profit_conversion_payout_formula() {
-        let x: u128 = kani::any::<u64>() as u128;
-        let h_num: u128 = kani::any::<u64>() as u128;
-        let h_den: u128 = kani::any::<u64>() as u128;
+    crate::tiered_proof! {
+        fn proof_effective_pnl_is_bounded<W>() {
+            let pnl: i128 = any_signed::<W>();
+            let h_num: u128 = any_unsigned::<W>();
+            let h_den: u128 = any_unsigned::<W>();
+            kani::assume(h_den > 0);
+            kani::assume(h_num <= h_den);
 
-        let y = effective_pnl(x as i128, h_num, h_den);
-        assert!(y <= x);
+            let payout = effective_pnl(pnl, h_num, h_den);
+            assert!(payout <= pnl.max(0) as u128);
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_effective_pnl_matches_reference_u64_domain() {
-        let pos: u8 = kani::any();
-        let h_den: u8 = kani::any();
-        let h_num: u8 = kani::any();
-        kani::assume(h_den > 0);
-        kani::assume(h_num <= h_den);
+    crate::tiered_proof! {
+        #[cfg_attr(kani, kani::unwind(5))]
+        fn proof_rounding_slack_bound_when_haircut_active<W>() {
+            crate::bounds::assert_haircut_slack_bounded::<W, 4>(effective_pnl);
+        }
+    }
 
-        let expected = (pos as u128 * h_num as u128) / (h_den as u128);
-        let actual = effective_pnl(pos as i128, h_num as u128, h_den as u128);
-        assert!(actual == expected);
+    crate::tiered_proof! {
+        #[cfg_attr(kani, kani::unwind(3))]
+        fn proof_rounding_slack_bound_two_accounts<W>() {
+            crate::bounds::assert_haircut_slack_bounded::<W, 2>(effective_pnl);
+        }
     }
 
-    #[kani::proof]
-    fn proof_effective_pnl_is_bounded() {
-        let pos: u8 = kani::any();
-        let h_den: u8 = kani::any();
-        let h_num: u8 = kani::any();
-        kani::assume(h_den > 0);
-        kani::assume(h_num <= h_den);
+    crate::tiered_proof! {
+        fn proof_effective_pnl_bounded<W>() {
+            let pnl: i128 = any_signed::<W>();
+            let h_num: u128 = any_unsigned::<W>();
+            let h_den: u128 = any_unsigned::<W>();
 
-        let payout = effective_pnl(pos as i128, h_num as u128, h_den as u128);
-        assert!(payout <= pos as u128);
+            let eff = effective_pnl(pnl, h_num, h_den);
+
+            let pos = pnl.max(0) as u128;
+            assert!(eff <= pos);
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_rounding_slack_bound_when_haircut_active() {
-        const N: usize = 4;
+    crate::tiered_proof! {
+        fn proof_effective_equity_with_haircut<W>() {
+            let capital: u128 = any_unsigned::<W>();
+            let pnl: i128 = any_signed::<W>();
+            let h_num: u128 = any_unsigned::<W>();
+            let h_den: u128 = any_unsigned::<W>();
 
-        let p1: u128 = kani::any::<u64>() as u128;
-        let p2: u128 = kani::any::<u64>() as u128;
-        let p3: u128 = kani::any::<u64>() as u128;
-        let p4: u128 = kani::any::<u64>() as u128;
+            let eff = effective_pnl(pnl, h_num, h_den);
+            let equity = capital.saturating_add(eff);
 
-        let pnl_pos_total = p1 + p2 + p3 + p4;
-        kani::assume(pnl_pos_total > 0);
+            assert!(equity >= capital);
+            assert!(equity - capital <= pnl.max(0) as u128);
+        }
+    }
 
-        let residual: u128 = kani::any::<u64>() as u128;
-        kani::assume(residual <= pnl_pos_total);
+    crate::tiered_proof! {
+        fn proof_warmup_monotonic_in_elapsed<W>() {
+            let profit: u128 = any_unsigned::<W>();
+            let t1: u64 = kani::any();
+            let t2: u64 = kani::any();
+            let period: u64 = kani::any();
+            kani::assume(t1 <= t2);
 
-        let mut sum_eff: u128 = 0;
-        let mut sum_r: u128 = 0;
+            let w1 = warmup_slope(profit, t1, period);
+            let w2 = warmup_slope(profit, t2, period);
 
-        for p in [p1, p2, p3, p4] {
-            let prod = p * residual;
-            sum_eff += prod / pnl_pos_total;
-            sum_r += prod % pnl_pos_total;
+            assert!(w1 <= w2);
         }
+    }
 
-        assert!(sum_eff <= residual);
+    crate::tiered_proof! {
+        fn proof_warmup_bounded_by_gross<W>() {
+            let profit: u128 = any_unsigned::<W>();
+            let elapsed: u64 = kani::any();
+            let period: u64 = kani::any();
 
-        // With residual <= pnl_pos_total, h_num = residual and h_den = pnl_pos_total.
-        // Each term is floored, so the "missing" amount is bounded by the number of accounts.
-        let slack = residual - sum_eff;
-        assert!(slack <= (N as u128) - 1);
+            let warmed = warmup_slope(profit, elapsed, period);
+            assert!(warmed <= profit);
+        }
+    }
+
+    crate::tiered_proof! {
+        fn proof_warmup_full_after_period<W>() {
+            let profit: u128 = any_unsigned::<W>();
+            let period: u64 = kani::any();
+            let elapsed: u64 = kani::any();
+            kani::assume(elapsed >= period);
+
+            let warmed = warmup_slope(profit, elapsed, period);
+            assert!(warmed == profit);
+        }
+    }
+
+    crate::tiered_proof! {
+        fn proof_fee_sweep_conservation<W>() {
+            let debt: u128 = any_unsigned::<W>();
+            let available: u128 = any_unsigned::<W>();
+
+            let (swept, remaining) = fee_debt_sweep(debt, available);
+
+            assert!(swept + remaining == debt);
+            assert!(swept <= available);
+            assert!(swept <= debt);
+        }
+    }
+
+    crate::tiered_proof! {
+        fn proof_fee_sweep_clears_when_sufficient<W>() {
+            let debt: u128 = any_unsigned::<W>();
+            let available: u128 = any_unsigned::<W>();
+            kani::assume(available >= debt);
+
+            let (swept, remaining) = fee_debt_sweep(debt, available);
+
+            assert!(swept == debt);
+            assert!(remaining == 0);
+        }
+    }
+
+    crate::tiered_proof! {
+        fn proof_funding_long_short_symmetry<W>() {
+            let position: u128 = any_unsigned::<W>();
+            let rate_num: i128 = any_signed::<W>();
+            let rate_den: u128 = any_unsigned::<W>();
+
+            let long_pay = funding_payment(position, rate_num, rate_den, true);
+            let short_pay = funding_payment(position, rate_num, rate_den, false);
+
+            assert!(long_pay == -short_pay);
+        }
+    }
+
+    crate::tiered_proof! {
+        fn proof_funding_zero_rate_no_payment<W>() {
+            let position: u128 = any_unsigned::<W>();
+            let rate_den: u128 = any_unsigned::<W>();
+            let is_long: bool = kani::any();
 
-        // Sanity: the remainder sum cannot cross N denominators.
-        assert!(sum_r < (N as u128) * pnl_pos_total);
+            let pay = funding_payment(position, 0, rate_den, is_long);
+            assert!(pay == 0);
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_effective_pnl_bounded() {
-        let pnl: i128 = kani::any::<i64>() as i128;
-        let h_num: u128 = kani::any::<u64>() as u128;
-        let h_den: u128 = kani::any::<u64>() as u128;
+    crate::tiered_proof! {
+        fn proof_funding_zero_denominator_safe<W>() {
+            let position: u128 = any_unsigned::<W>();
+            let rate_num: i128 = any_signed::<W>();
+            let is_long: bool = kani::any();
+
+            let pay = funding_payment(position, rate_num, 0, is_long);
+            assert!(pay == 0);
+        }
+    }
 
-        let eff = effective_pnl(pnl, h_num, h_den);
+    crate::tiered_proof! {
+        fn proof_funding_zero_position_no_payment<W>() {
+            let rate_num: i128 = any_signed::<W>();
+            let rate_den: u128 = any_unsigned::<W>();
+            let is_long: bool = kani::any();
 
-        let pos = pnl.max(0) as u128;
-        assert!(eff <= pos);
+            let pay = funding_payment(0, rate_num, rate_den, is_long);
+            assert!(pay == 0);
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_effective_equity_with_haircut() {
-        let capital: u128 = kani::any::<u64>() as u128;
-        let pnl: i128 = kani::any::<i64>() as i128;
-        let h_num: u128 = kani::any::<u64>() as u128;
-        let h_den: u128 = kani::any::<u64>() as u128;
+    crate::tiered_proof! {
+        fn proof_writeoff_conservation<W>() {
+            let neg_equity: u128 = any_unsigned::<W>();
+            let insurance: u128 = any_unsigned::<W>();
+
+            let (writeoff, new_insurance) = loss_writeoff(neg_equity, insurance);
+
+            assert!(writeoff + new_insurance == insurance);
+            assert!(writeoff <= neg_equity);
+            assert!(writeoff <= insurance);
+        }
+    }
+
+    crate::tiered_proof! {
+        fn proof_writeoff_insurance_monotonic_decrease<W>() {
+            let neg1: u128 = any_unsigned::<W>();
+            let neg2: u128 = any_unsigned::<W>();
+            let insurance: u128 = any_unsigned::<W>();
+            kani::assume(neg1 <= neg2);
+
+            let (_, ins_after_1) = loss_writeoff(neg1, insurance);
+            let (_, ins_after_2) = loss_writeoff(neg2, insurance);
+
+            assert!(ins_after_1 >= ins_after_2);
+        }
+    }
+
+    crate::tiered_proof! {
+        fn proof_margin_ratio_never_divides_by_zero<W>() {
+            let equity: i128 = any_signed::<W>();
+            let notional: u128 = any_unsigned::<W>();
+
+            let ratio = margin_ratio_bps(equity, notional);
+            if notional == 0 {
+                assert!(ratio == u128::MAX);
+            } else if equity <= 0 {
+                assert!(ratio == 0);
+            }
+        }
+    }
 
-        let eff = effective_pnl(pnl, h_num, h_den);
-        let equity = capital + eff;
+    crate::tiered_proof! {
+        fn proof_margin_requirement_rounds_up<W>() {
+            let notional: u128 = any_unsigned::<W>();
+            let bps: u64 = kani::any();
+            kani::assume(bps <= BPS_DENOMINATOR);
+            kani::assume(notional <= u128::MAX / (2 * BPS_DENOMINATOR as u128));
+
+            let req = margin_requirement(notional, bps);
+            // `req` is the least value with `req * 10_000 >= notional * bps`.
+            let exact = notional * bps as u128;
+            assert!(req * 10_000 >= exact);
+            assert!(req == 0 || (req - 1) * 10_000 < exact);
+        }
+    }
 
-        assert!(equity >= capital);
+    // Liquidatability is checked without an active haircut: with `h < 1` the
+    // haircut equity of a profitable account can fall slower than the
+    // maintenance requirement, which is the intended Percolator behaviour.
+    crate::tiered_proof! {
+        fn proof_liquidatable_monotone_in_adverse_price<W>() {
+            let size: i128 = any_signed::<W>();
+            let entry: u128 = any_unsigned::<W>();
+            let p1: u128 = any_unsigned::<W>();
+            let p2: u128 = any_unsigned::<W>();
+            let capital: u128 = any_unsigned::<W>();
+            let fee_debt: u128 = any_unsigned::<W>();
+            let mm_bps: u64 = kani::any();
+            kani::assume(mm_bps <= BPS_DENOMINATOR);
+            // Adverse: price falls for a long, rises for a short.
+            kani::assume(if size >= 0 { p2 <= p1 } else { p2 >= p1 });
+
+            // Stay clear of the saturation boundaries at 128-bit width.
+            let bound = i128::MAX as u128 / 4;
+            let max_price = entry.max(p1).max(p2);
+            kani::assume(capital <= bound && fee_debt <= bound);
+            kani::assume(size.unsigned_abs().checked_mul(max_price).is_some_and(|x| x <= bound));
+
+            let liq_at = |price: u128| {
+                let pnl = unrealized_pnl(size, entry, price);
+                let equity = account_equity(capital, pnl, 1, 1, fee_debt);
+                is_liquidatable(equity, position_notional(size, price), mm_bps)
+            };
+
+            if liq_at(p1) {
+                assert!(liq_at(p2));
+            }
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_warmup_monotonic_in_elapsed() {
-        let profit: u128 = kani::any::<u64>() as u128;
-        let t1: u64 = kani::any();
-        let t2: u64 = kani::any();
-        let period: u64 = kani::any();
-        kani::assume(t1 <= t2);
+    crate::tiered_proof! {
+        fn proof_healthy_account_stays_healthy_after_deposit<W>() {
+            let capital: u128 = any_unsigned::<W>();
+            let deposit: u128 = any_unsigned::<W>();
+            let pnl: i128 = any_signed::<W>();
+            let h_num: u128 = any_unsigned::<W>();
+            let h_den: u128 = any_unsigned::<W>();
+            let fee_debt: u128 = any_unsigned::<W>();
+            let notional: u128 = any_unsigned::<W>();
+            let mm_bps: u64 = kani::any();
+            kani::assume(capital.checked_add(deposit).is_some());
+
+            let before = account_equity(capital, pnl, h_num, h_den, fee_debt);
+            let after = account_equity(capital + deposit, pnl, h_num, h_den, fee_debt);
+
+            assert!(after >= before);
+            if !is_liquidatable(before, notional, mm_bps) {
+                assert!(!is_liquidatable(after, notional, mm_bps));
+            }
+        }
+    }
 
-        let w1 = warmup_slope(profit, t1, period);
-        let w2 = warmup_slope(profit, t2, period);
+    /// Draws `(size, entry, capital, maintenance_bps)` clear of the saturation
+    /// boundaries, plus the bound that prices times `|size|` must stay under.
+    fn any_bounded_position<W: crate::tier::Width>() -> (i128, u128, u128, u64, u128) {
+        let size: i128 = any_signed::<W>();
+        let entry: u128 = any_unsigned::<W>();
+        let capital: u128 = any_unsigned::<W>();
+        let mm_bps: u64 = kani::any();
+        kani::assume(mm_bps <= BPS_DENOMINATOR);
+
+        let bound = u128::MAX / (8 * BPS_DENOMINATOR as u128);
+        kani::assume(capital <= bound && size.unsigned_abs() <= bound);
+        kani::assume(
+            size.unsigned_abs()
+                .checked_mul(entry)
+                .is_some_and(|x| x <= bound),
+        );
+        (size, entry, capital, mm_bps, bound)
+    }
 
-        assert!(w1 <= w2);
+    fn liquidatable_at(size: i128, entry: u128, capital: u128, mm_bps: u64, price: u128) -> bool {
+        let equity = account_equity(capital, unrealized_pnl(size, entry, price), 1, 1, 0);
+        is_liquidatable(equity, position_notional(size, price), mm_bps)
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_warmup_bounded_by_gross() {
-        let profit: u128 = kani::any::<u64>() as u128;
-        let elapsed: u64 = kani::any();
-        let period: u64 = kani::any();
+    crate::tiered_proof! {
+        fn proof_liquidation_price_round_trip<W>() {
+            let (size, entry, capital, mm_bps, bound) = any_bounded_position::<W>();
+            let abs = size.unsigned_abs();
+
+            if let Some(liq) = liquidation_price(size, entry, capital, mm_bps) {
+                kani::assume(abs.checked_mul(liq).is_some_and(|x| x <= bound));
+                assert!(liquidatable_at(size, entry, capital, mm_bps, liq));
+
+                // One price unit back is healthy, so the shortfall at `liq` is
+                // at most one unit of PnL plus one unit of requirement, as
+                // documented on `liquidation_price`.
+                let healthy = if size > 0 { liq.checked_add(1) } else { liq.checked_sub(1) };
+                if let Some(healthy) = healthy {
+                    assert!(!liquidatable_at(size, entry, capital, mm_bps, healthy));
+
+                    let equity = capital as i128 + unrealized_pnl(size, entry, liq);
+                    let req = margin_requirement(position_notional(size, liq), mm_bps) as i128;
+                    let tick = abs + margin_requirement(abs, mm_bps);
+                    assert!(req > equity && req - equity <= tick as i128);
+                }
+            }
+        }
+    }
 
-        let warmed = warmup_slope(profit, elapsed, period);
-        assert!(warmed <= profit);
+    crate::tiered_proof! {
+        fn proof_liquidation_price_agrees_with_is_liquidatable<W>() {
+            let (size, entry, capital, mm_bps, bound) = any_bounded_position::<W>();
+            let price: u128 = any_unsigned::<W>();
+            kani::assume(size.unsigned_abs().checked_mul(price).is_some_and(|x| x <= bound));
+
+            let liquidatable = liquidatable_at(size, entry, capital, mm_bps, price);
+            match liquidation_price(size, entry, capital, mm_bps) {
+                Some(liq) if size > 0 => assert!(liquidatable == (price <= liq)),
+                Some(liq) => assert!(liquidatable == (price >= liq)),
+                None => assert!(!liquidatable),
+            }
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_warmup_full_after_period() {
-        let profit: u128 = kani::any::<u64>() as u128;
-        let period: u64 = kani::any();
-        let elapsed: u64 = kani::any();
-        kani::assume(elapsed >= period);
+    crate::tiered_proof! {
+        fn proof_checked_haircut_and_pnl_agree_when_ok<W>() {
+            let vault: u128 = any_unsigned::<W>();
+            let principal_total: u128 = any_unsigned::<W>();
+            let insurance: u128 = any_unsigned::<W>();
+            let pnl_pos_total: u128 = any_unsigned::<W>();
+            let pnl: i128 = any_signed::<W>();
+            let h_num: u128 = any_unsigned::<W>();
+            let h_den: u128 = any_unsigned::<W>();
+
+            if let Ok(h) = checked_haircut_ratio(vault, principal_total, insurance, pnl_pos_total) {
+                assert!(h == haircut_ratio(vault, principal_total, insurance, pnl_pos_total));
+            }
+            match checked_effective_pnl(pnl, h_num, h_den) {
+                Ok(y) => assert!(y == effective_pnl(pnl, h_num, h_den)),
+                Err(RiskError::ZeroDenominator) => assert!(h_den == 0),
+                Err(RiskError::InvalidRatio) => assert!(h_num > h_den),
+                Err(RiskError::Overflow) => assert!(h_num < h_den),
+            }
+            if h_den > 0 {
+                assert!(checked_effective_pnl(pnl, h_den, h_den) == Ok(pnl.max(0) as u128));
+            }
+        }
+    }
 
-        let warmed = warmup_slope(profit, elapsed, period);
-        assert!(warmed == profit);
+    crate::tiered_proof! {
+        fn proof_checked_funding_payment_agrees_when_ok<W>() {
+            let position: u128 = any_unsigned::<W>();
+            let rate_num: i128 = any_signed::<W>();
+            let rate_den: u128 = any_unsigned::<W>();
+            let is_long: bool = kani::any();
+
+            match checked_funding_payment(position, rate_num, rate_den, is_long) {
+                Ok(p) => assert!(p == funding_payment(position, rate_num, rate_den, is_long)),
+                Err(RiskError::ZeroDenominator) => assert!(rate_den == 0),
+                Err(_) => {
+                    // Exactly the inputs the saturating version clamps.
+                    let payment = funding_payment(position, rate_num, rate_den, is_long);
+                    assert!(payment.unsigned_abs() == i128::MAX as u128);
+                }
+            }
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_fee_sweep_conservation() {
-        let debt: u128 = kani::any::<u64>() as u128;
-        let available: u128 = kani::any::<u64>() as u128;
+    crate::tiered_proof! {
+        fn proof_checked_margin_helpers_agree_when_ok<W>() {
+            let size: i128 = any_signed::<W>();
+            let entry: u128 = any_unsigned::<W>();
+            let mark: u128 = any_unsigned::<W>();
+            let notional: u128 = any_unsigned::<W>();
+            let equity: i128 = any_signed::<W>();
+            let bps: u64 = kani::any();
+
+            if let Ok(pnl) = checked_unrealized_pnl(size, entry, mark) {
+                assert!(pnl == unrealized_pnl(size, entry, mark));
+            }
+            if let Ok(n) = checked_position_notional(size, mark) {
+                assert!(n == position_notional(size, mark));
+            }
+            if let Ok(req) = checked_margin_requirement(notional, bps) {
+                assert!(req == margin_requirement(notional, bps));
+            }
+            match checked_margin_ratio_bps(equity, notional) {
+                Ok(ratio) => assert!(ratio == margin_ratio_bps(equity, notional)),
+                Err(RiskError::ZeroDenominator) => assert!(notional == 0),
+                Err(_) => {}
+            }
+        }
+    }
 
-        let (swept, remaining) = fee_debt_sweep(debt, available);
+    crate::tiered_proof! {
+        fn proof_checked_account_equity_agrees_when_ok<W>() {
+            let capital: u128 = any_unsigned::<W>();
+            let pnl: i128 = any_signed::<W>();
+            let h_num: u128 = any_unsigned::<W>();
+            let h_den: u128 = any_unsigned::<W>();
+            let fee_debt: u128 = any_unsigned::<W>();
 
-        assert!(swept + remaining == debt);
-        assert!(swept <= available);
-        assert!(swept <= debt);
+            if let Ok(equity) = checked_account_equity(capital, pnl, h_num, h_den, fee_debt) {
+                assert!(equity == account_equity(capital, pnl, h_num, h_den, fee_debt));
+            }
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_fee_sweep_clears_when_sufficient() {
-        let debt: u128 = kani::any::<u64>() as u128;
-        let available: u128 = kani::any::<u64>() as u128;
-        kani::assume(available >= debt);
+    crate::tiered_proof! {
+        fn proof_full_close_realizes_unrealized_pnl<W>() {
+            let position = Position {
+                size: any_signed::<W>(),
+                entry_price: any_unsigned::<W>(),
+            };
+            let mark: u128 = any_unsigned::<W>();
+            kani::assume(position.size != 0 && position.size != i128::MIN);
+
+            if let Ok((closed, realized)) = position.fill(-position.size, mark) {
+                assert!(closed == Position::default());
+                assert!(realized == position.unrealized_pnl(mark));
+            }
+        }
+    }
 
-        let (swept, remaining) = fee_debt_sweep(debt, available);
+    const FILLS: usize = 3;
+
+    // Realized plus unrealized PnL equals the exact cash-flow total
+    // `mark * size - sum(delta * price)`, which depends only on the fills and
+    // not on how they split into opens, reductions and flips. Rounding of the
+    // average entry only ever favours the protocol, by less than one unit per
+    // unit of size held after each fill.
+    crate::tiered_proof! {
+        #[cfg_attr(kani, kani::unwind(4))]
+        fn proof_position_pnl_is_path_independent<W>() {
+            let mark: u128 = any_unsigned::<W>();
+            let mut position = Position::default();
+            let mut realized: Option<i128> = Some(0);
+            let mut cash: Option<i128> = Some(0);
+            let mut slack: u128 = 0;
+
+            let mut k = 0;
+            while k < FILLS {
+                let delta: i128 = any_signed::<W>();
+                let price: u128 = any_unsigned::<W>();
+                let Ok((next, r)) = position.fill(delta, price) else {
+                    return;
+                };
+                position = next;
+                realized = realized.and_then(|x| x.checked_add(r));
+                cash = cash.and_then(|c| {
+                    let flow = delta.checked_mul(i128::try_from(price).ok()?)?;
+                    c.checked_sub(flow)
+                });
+                slack = slack.saturating_add(position.size.unsigned_abs());
+                k += 1;
+            }
 
-        assert!(swept == debt);
-        assert!(remaining == 0);
+            let exact = cash.and_then(|c| {
+                c.checked_add(position.size.checked_mul(i128::try_from(mark).ok()?)?)
+            });
+            let unrealized = checked_unrealized_pnl(position.size, position.entry_price, mark);
+            let total = realized.and_then(|x| x.checked_add(unrealized.ok()?));
+            kani::assume(exact.is_some() && total.is_some() && slack <= i128::MAX as u128);
+            let (exact, total) = (exact.unwrap(), total.unwrap());
+            assert!(total <= exact);
+            assert!(exact - total <= slack as i128);
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_funding_long_short_symmetry() {
-        let position: u128 = kani::any::<u64>() as u128;
-        let rate_num: i128 = kani::any::<i64>() as i128;
-        let rate_den: u128 = kani::any::<u64>() as u128;
+    const WARMUP_STEPS: usize = 4;
 
-        let long_pay = funding_payment(position, rate_num, rate_den, true);
-        let short_pay = funding_payment(position, rate_num, rate_den, false);
+    fn any_slots<W: crate::tier::Width>() -> u64 {
+        any_unsigned::<W>().min(u64::MAX as u128) as u64
+    }
 
-        assert!(long_pay == -short_pay);
+    crate::tiered_proof! {
+        #[cfg_attr(kani, kani::unwind(5))]
+        fn proof_warmup_withdrawals_bounded_by_warmed_profit<W>() {
+            let mut state = WarmupState::new(any_slots::<W>());
+            // Ghost totals across epochs; kept clear of `u128` overflow.
+            let mut profit_in: u128 = 0;
+            let mut warmed_before_resets: u128 = 0;
+            let mut withdrawn_total: u128 = 0;
+
+            let mut step = 0;
+            while step < WARMUP_STEPS {
+                let before = state;
+                let mut reset = false;
+                match kani::any::<u8>() % 3 {
+                    0 => {
+                        let haircut_active: bool = kani::any();
+                        let (h_num, h_den) = if haircut_active { (0, 1) } else { (1, 1) };
+                        state.accrue(any_slots::<W>(), h_num, h_den);
+                        if haircut_active {
+                            assert!(state == before);
+                        }
+                    }
+                    1 => {
+                        withdrawn_total += state.withdraw(any_unsigned::<W>());
+                    }
+                    _ => {
+                        let profit = any_unsigned::<W>();
+                        kani::assume(profit <= u128::MAX / 8);
+                        if state.on_new_profit(profit).is_ok() {
+                            profit_in += profit;
+                            warmed_before_resets += before.warmed();
+                            reset = true;
+                        } else {
+                            assert!(state == before);
+                        }
+                    }
+                }
+                if !reset {
+                    assert!(state.warmed() >= before.warmed());
+                }
+                assert!(withdrawn_total <= warmed_before_resets + state.warmed());
+                assert!(
+                    profit_in == state.vested + state.gross_profit - state.withdrawn + withdrawn_total
+                );
+                step += 1;
+            }
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_funding_zero_rate_no_payment() {
-        let position: u128 = kani::any::<u64>() as u128;
-        let rate_den: u128 = kani::any::<u64>() as u128;
-        let is_long: bool = kani::any();
+    crate::tiered_proof! {
+        fn proof_socialized_loss_conserves_value<W>() {
+            let negative_equity: u128 = any_unsigned::<W>();
+            let insurance: u128 = any_unsigned::<W>();
+            let pnl_pos_total: u128 = any_unsigned::<W>();
+
+            let s = socialize_loss(negative_equity, insurance, pnl_pos_total);
+            assert!(s.covered + s.socialized + s.unabsorbed == negative_equity);
+            assert!(s.insurance + s.covered == insurance);
+            assert!(s.pnl_pos_total + s.socialized == pnl_pos_total);
+
+            // Insurance is senior: nothing is socialized while it lasts.
+            assert!(s.socialized == 0 || s.insurance == 0);
+            if insurance.checked_add(pnl_pos_total).is_some_and(|x| x >= negative_equity) {
+                assert!(s.covered + s.socialized == negative_equity);
+            }
+            crate::cover!(s.covered > 0 && s.socialized > 0, "loss split across insurance and pnl");
+        }
+    }
 
-        let pay = funding_payment(position, 0, rate_den, is_long);
-        assert!(pay == 0);
+    const LOSS_ACCOUNTS: usize = 3;
+
+    crate::tiered_proof! {
+        #[cfg_attr(kani, kani::unwind(4))]
+        fn proof_socialized_loss_keeps_payouts_within_residual<W>() {
+            let vault: u128 = any_unsigned::<W>();
+            let principal_total: u128 = any_unsigned::<W>();
+            let insurance: u128 = any_unsigned::<W>();
+            let negative_equity: u128 = any_unsigned::<W>();
+            let mut pnl = [0i128; LOSS_ACCOUNTS];
+            let mut pnl_pos_total: u128 = 0;
+            let mut i = 0;
+            while i < LOSS_ACCOUNTS {
+                pnl[i] = any_signed::<W>();
+                pnl_pos_total = pnl_pos_total.saturating_add(pnl[i].max(0) as u128);
+                i += 1;
+            }
+            kani::assume(pnl_pos_total < u128::MAX);
+
+            let s = socialize_loss(negative_equity, insurance, pnl_pos_total);
+            let taken = socialize_pro_rata(&pnl, s.socialized);
+
+            let (mut taken_total, mut pos_after) = (0u128, 0u128);
+            let mut i = 0;
+            while i < LOSS_ACCOUNTS {
+                assert!(taken[i] <= pnl[i].max(0) as u128);
+                pnl[i] -= taken[i] as i128;
+                taken_total += taken[i];
+                pos_after += pnl[i].max(0) as u128;
+                i += 1;
+            }
+            assert!(taken_total == s.socialized);
+            assert!(pos_after == s.pnl_pos_total);
+
+            let (h_num, h_den) = haircut_ratio(vault, principal_total, s.insurance, pos_after);
+            let mut paid: u128 = 0;
+            let mut i = 0;
+            while i < LOSS_ACCOUNTS {
+                paid += effective_pnl(pnl[i], h_num, h_den);
+                i += 1;
+            }
+            let residual = vault.saturating_sub(principal_total.saturating_add(s.insurance));
+            assert!(paid <= residual);
+            crate::cover!(s.socialized > 0 && h_num < h_den, "socialized loss under active haircut");
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_funding_zero_denominator_safe() {
-        let position: u128 = kani::any::<u64>() as u128;
-        let rate_num: i128 = kani::any::<i64>() as i128;
-        let is_long: bool = kani::any();
+    const TRADERS: usize = 3;
+    const TRADES: usize = 2;
+
+    /// `(long OI, short OI, sum of cash + capital, sum of fee debt)`, or
+    /// `None` if a sum leaves its type.
+    fn trade_totals(accounts: &[TradeAccount; TRADERS]) -> Option<(u128, u128, i128, u128)> {
+        let (mut long, mut short, mut value, mut debt) = (0u128, 0u128, 0i128, 0u128);
+        let mut i = 0;
+        while i < TRADERS {
+            let a = accounts[i];
+            if a.size > 0 {
+                long = long.checked_add(a.size.unsigned_abs())?;
+            } else {
+                short = short.checked_add(a.size.unsigned_abs())?;
+            }
+            value = value
+                .checked_add(a.cash)?
+                .checked_add(i128::try_from(a.capital).ok()?)?;
+            debt = debt.checked_add(a.fee_debt)?;
+            i += 1;
+        }
+        Some((long, short, value, debt))
+    }
 
-        let pay = funding_payment(position, rate_num, 0, is_long);
-        assert!(pay == 0);
+    crate::tiered_proof! {
+        #[cfg_attr(kani, kani::unwind(4))]
+        fn proof_fills_balance_oi_and_conserve_cash<W>() {
+            let mut accounts = [TradeAccount::default(); TRADERS];
+            let mut i = 0;
+            while i < TRADERS {
+                accounts[i].capital = any_unsigned::<W>();
+                accounts[i].fee_debt = any_unsigned::<W>();
+                i += 1;
+            }
+
+            let mut k = 0;
+            while k < TRADES {
+                let taker = kani::any::<u8>() as usize % TRADERS;
+                let maker = kani::any::<u8>() as usize % TRADERS;
+                kani::assume(taker != maker);
+                let size: i128 = any_signed::<W>();
+                let price: u128 = any_unsigned::<W>();
+                let (taker_bps, maker_bps): (u64, u64) = (kani::any(), kani::any());
+
+                let before = trade_totals(&accounts);
+                let (mut t, mut m) = (accounts[taker], accounts[maker]);
+                match match_fill(&mut t, &mut m, size, price, taker_bps, maker_bps) {
+                    Ok(fees) => {
+                        accounts[taker] = t;
+                        accounts[maker] = m;
+                        let after = trade_totals(&accounts);
+                        kani::assume(before.is_some() && after.is_some());
+                        let (before, after) = (before.unwrap(), after.unwrap());
+
+                        // Checked forms: both sides overflow together or not at all.
+                        assert!(after.0 == after.1);
+                        assert!(before.2.checked_sub(after.2) == i128::try_from(fees.collected).ok());
+                        assert!(after.3.checked_add(fees.collected) == before.3.checked_add(fees.charged));
+                    }
+                    Err(_) => assert!(t == accounts[taker] && m == accounts[maker]),
+                }
+                k += 1;
+            }
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_funding_zero_position_no_payment() {
-        let rate_num: i128 = kani::any::<i64>() as i128;
-        let rate_den: u128 = kani::any::<u64>() as u128;
-        let is_long: bool = kani::any();
+    const FEE_TIERS: usize = 3;
+
+    /// Fee table with thresholds ascending and every ratio at most 100%; the
+    /// tier fees and rebates are otherwise unconstrained.
+    fn any_fee_schedule<W: crate::tier::Width>() -> FeeSchedule<FEE_TIERS> {
+        let mut tiers = [FeeTier::default(); FEE_TIERS];
+        let mut min_volume: u128 = 0;
+        let mut i = 0;
+        while i < FEE_TIERS {
+            let next = min_volume.checked_add(any_unsigned::<W>());
+            kani::assume(next.is_some());
+            min_volume = next.unwrap();
+            tiers[i] = FeeTier {
+                min_volume,
+                taker_bps: any_bps(),
+                maker_rebate_bps: any_bps(),
+            };
+            i += 1;
+        }
+        FeeSchedule {
+            tiers,
+            referral_bps: any_bps(),
+        }
+    }
 
-        let pay = funding_payment(0, rate_num, rate_den, is_long);
-        assert!(pay == 0);
+    crate::tiered_proof! {
+        #[cfg_attr(kani, kani::unwind(4))]
+        fn proof_fee_split_is_exact<W>() {
+            let schedule = any_fee_schedule::<W>();
+            let notional: u128 = any_unsigned::<W>();
+            let volume: u128 = any_unsigned::<W>();
+
+            // Holds without `is_valid`: fees may rise with volume and rebates
+            // may exceed the taker fee.
+            let fees = schedule.charge(notional, volume);
+            assert!(fees.maker_rebate <= fees.taker_fee);
+            assert!(fees.taker_fee == fees.maker_rebate + fees.referral + fees.protocol);
+            crate::cover!(
+                fees.maker_rebate > 0 && fees.referral > 0 && fees.protocol > 0,
+                "fee split into all three parts"
+            );
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_writeoff_conservation() {
-        let neg_equity: u128 = kani::any::<u64>() as u128;
-        let insurance: u128 = kani::any::<u64>() as u128;
+    crate::tiered_proof! {
+        #[cfg_attr(kani, kani::unwind(4))]
+        fn proof_fee_is_monotone_in_notional<W>() {
+            let schedule = any_fee_schedule::<W>();
+            let (n1, n2): (u128, u128) = (any_unsigned::<W>(), any_unsigned::<W>());
+            let volume: u128 = any_unsigned::<W>();
+            kani::assume(n1 <= n2);
+
+            assert!(schedule.charge(n1, volume).taker_fee <= schedule.charge(n2, volume).taker_fee);
+        }
+    }
 
-        let (writeoff, new_insurance) = loss_writeoff(neg_equity, insurance);
+    crate::tiered_proof! {
+        #[cfg_attr(kani, kani::unwind(4))]
+        fn proof_fee_never_rises_with_volume<W>() {
+            let schedule = any_fee_schedule::<W>();
+            let notional: u128 = any_unsigned::<W>();
+            let (v1, v2): (u128, u128) = (any_unsigned::<W>(), any_unsigned::<W>());
+            crate::cover!(schedule.is_valid(), "valid fee schedule");
+            kani::assume(schedule.is_valid() && v1 <= v2);
 
-        assert!(writeoff + new_insurance == insurance);
-        assert!(writeoff <= neg_equity);
-        assert!(writeoff <= insurance);
+            assert!(schedule.charge(notional, v2).taker_fee <= schedule.charge(notional, v1).taker_fee);
+        }
     }
 
-    #[cfg(feature = "kani-full")]
-    #[kani::proof]
-    fn proof_writeoff_insurance_monotonic_decrease() {
-        let neg1: u128 = kani::any::<u64>() as u128;
-        let neg2: u128 = kani::any::<u64>() as u128;
-        let insurance: u128 = kani::any::<u64>() as u128;
-        kani::assume(neg1 <= neg2);
+    #[cfg(feature = "kani-contracts")]
+    mod contracts {
+        use super::*;
+
+        #[kani::proof_for_contract(haircut_ratio)]
+        fn contract_haircut_ratio() {
+            let _ = haircut_ratio(kani::any(), kani::any(), kani::any(), kani::any());
+        }
+
+        #[kani::proof_for_contract(effective_pnl)]
+        fn contract_effective_pnl() {
+            let _ = effective_pnl(kani::any(), kani::any(), kani::any());
+        }
 
-        let (_, ins_after_1) = loss_writeoff(neg1, insurance);
-        let (_, ins_after_2) = loss_writeoff(neg2, insurance);
+        #[kani::proof_for_contract(warmup_slope)]
+        fn contract_warmup_slope() {
+            let _ = warmup_slope(kani::any(), kani::any(), kani::any());
+        }
+
+        #[kani::proof_for_contract(fee_debt_sweep)]
+        fn contract_fee_debt_sweep() {
+            let _ = fee_debt_sweep(kani::any(), kani::any());
+        }
 
-        assert!(ins_after_1 >= ins_after_2);
+        #[kani::proof_for_contract(funding_payment)]
+        fn contract_funding_payment() {
+            let _ = funding_payment(kani::any(), kani::any(), kani::any(), kani::any());
+        }
+
+        #[kani::proof_for_contract(loss_writeoff)]
+        fn contract_loss_writeoff() {
+            let _ = loss_writeoff(kani::any(), kani::any());
+        }
+
+        #[kani::proof_for_contract(socialize_loss)]
+        fn contract_socialize_loss() {
+            let _ = socialize_loss(kani::any(), kani::any(), kani::any());
+        }
+
+        #[kani::proof_for_contract(margin_requirement)]
+        fn contract_margin_requirement() {
+            let _ = margin_requirement(kani::any(), kani::any());
+        }
+
+        #[kani::proof_for_contract(margin_ratio_bps)]
+        fn contract_margin_ratio_bps() {
+            let _ = margin_ratio_bps(kani::any(), kani::any());
+        }
     }
 }
@@ -0,0 +1,506 @@
+//! Randomized fallback backend for running helpers under plain `cargo test`.
+//!
+//! [`any`] draws concrete values biased towards edge cases (`0`, `1`, `MIN`,
+//! `MAX`, `-1`) and small magnitudes, and [`assume`] rejects the current
+//! sample. Helpers must be driven through [`check`], which reruns the harness
+//! until enough samples pass their assumptions.
+//!
+//! This is sampling, not proof: use it for fast PR feedback and keep
+//! `cargo kani` for the full symbolic run.
+//!
+//! When a sample fails, [`check`] prints the concrete inputs the harness drew
+//! and a ready-to-paste `#[test]` that feeds the same draws back through
+//! [`replay`], so a counterexample becomes a permanent regression test. The
+//! test calls a named harness function directly; closures need
+//! [`check!`](crate::check), which records their body.
+//!
+//! Environment:
+//! - `KANI_SOLANA_CASES`: accepted samples per [`check`] (default 256).
+//! - `KANI_SOLANA_SEED`: base seed (default fixed, so runs are reproducible).
+
+use std::any::{type_name, Any};
+use std::cell::{Cell, RefCell};
+use std::fmt::{Debug, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+const DEFAULT_CASES: u64 = 256;
+const DEFAULT_SEED: u64 = 0x5EED_CA5E_F00D_u64;
+const MAX_REJECTS_PER_CASE: u64 = 64;
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+thread_local! {
+    static STATE: Cell<u64> = const { Cell::new(DEFAULT_SEED) };
+    static COVERS: RefCell<Vec<(&'static str, bool)>> = const { RefCell::new(Vec::new()) };
+    static TAPE: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+    static SCRIPT: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+    static INPUTS: RefCell<Vec<(&'static str, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Panic payload used by [`assume`] to discard a sample.
+#[derive(Debug, Clone, Copy)]
+pub struct Rejected;
+
+/// Next raw 64-bit draw.
+///
+/// Comes from the replay tape while [`replay`] is running, otherwise from the
+/// thread-local SplitMix64 generator. Every draw is recorded on the tape.
+pub fn next_u64() -> u64 {
+    let v = SCRIPT
+        .with(|s| s.borrow_mut().pop())
+        .unwrap_or_else(splitmix_next);
+    TAPE.with(|t| t.borrow_mut().push(v));
+    v
+}
+
+fn splitmix_next() -> u64 {
+    STATE.with(|s| {
+        let next = s.get().wrapping_add(GOLDEN_GAMMA);
+        s.set(next);
+        let mut z = next;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    })
+}
+
+fn next_u128() -> u128 {
+    ((next_u64() as u128) << 64) | (next_u64() as u128)
+}
+
+/// Types that can be sampled by the runtime backend.
+///
+/// Mirrors `kani::Arbitrary`; implement it for your own state and operation
+/// types when running harnesses under `cargo test`. The `Debug` bound lets
+/// counterexamples report the concrete inputs.
+pub trait Arbitrary: Sized + Debug {
+    /// Draw one value.
+    fn any() -> Self;
+}
+
+/// Arbitrary value of type `T`.
+pub fn any<T: Arbitrary>() -> T {
+    let v = T::any();
+    record_input(&v);
+    v
+}
+
+fn record_input<T: Debug>(v: &T) {
+    INPUTS.with(|i| i.borrow_mut().push((type_name::<T>(), format!("{v:?}"))));
+}
+
+/// Reject the current sample unless `cond` holds.
+pub fn assume(cond: bool) {
+    if !cond {
+        panic::panic_any(Rejected);
+    }
+}
+
+/// Record whether a [`cover!`](crate::cover) condition held for this sample.
+pub fn cover(cond: bool, msg: &'static str) {
+    COVERS.with(|c| {
+        let mut covers = c.borrow_mut();
+        match covers.iter_mut().find(|(m, _)| *m == msg) {
+            Some((_, hit)) => *hit |= cond,
+            None => covers.push((msg, cond)),
+        }
+    });
+}
+
+macro_rules! impl_int {
+    ($($t:ty),* $(,)?) => {$(
+        impl Arbitrary for $t {
+            fn any() -> Self {
+                let edges: [$t; 6] = [
+                    0,
+                    1,
+                    <$t>::MAX,
+                    <$t>::MIN,
+                    <$t>::MAX - 1,
+                    (0 as $t).wrapping_sub(1),
+                ];
+                match next_u64() % 8 {
+                    0 | 1 => edges[(next_u64() % edges.len() as u64) as usize],
+                    2..=5 => {
+                        // Log-uniform magnitude so narrow `assume` ranges still hit.
+                        let width = (next_u64() % (<$t>::BITS as u64 + 1)) as u32;
+                        let mask = if width == 128 { u128::MAX } else { (1u128 << width) - 1 };
+                        let v = (next_u128() & mask) as $t;
+                        if next_u64() % 2 == 0 { v } else { v.wrapping_neg() }
+                    }
+                    _ => next_u128() as $t,
+                }
+            }
+        }
+    )*};
+}
+
+impl_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Integers that can be sampled directly from a closed interval.
+pub trait Ranged: Arbitrary + PartialOrd + Copy {
+    /// Draw one value in `[min, max]`, rejecting the sample if `min > max`.
+    fn any_in_range(min: Self, max: Self) -> Self;
+}
+
+/// Arbitrary value in `[min, max]` inclusive, biased towards both ends.
+pub fn any_in_range<T: Ranged>(min: T, max: T) -> T {
+    let v = T::any_in_range(min, max);
+    record_input(&v);
+    v
+}
+
+macro_rules! impl_ranged {
+    ($($t:ty => $u:ty),* $(,)?) => {$(
+        impl Ranged for $t {
+            fn any_in_range(min: Self, max: Self) -> Self {
+                assume(min <= max);
+                let span = (max as $u).wrapping_sub(min as $u) as u128;
+                let offset = match next_u64() % 8 {
+                    0 => 0,
+                    1 => span,
+                    2 => 1u128.min(span),
+                    3..=5 => {
+                        let width = (next_u64() % 129) as u32;
+                        let mask = if width == 128 { u128::MAX } else { (1u128 << width) - 1 };
+                        next_u128() & mask
+                    }
+                    _ => next_u128(),
+                };
+                let offset = match span.checked_add(1) {
+                    Some(len) => offset % len,
+                    None => offset,
+                };
+                (min as $u).wrapping_add(offset as $u) as $t
+            }
+        }
+    )*};
+}
+
+impl_ranged!(
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, u128 => u128, usize => usize,
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize,
+);
+
+impl Arbitrary for bool {
+    fn any() -> Self {
+        next_u64() & 1 == 1
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Option<T> {
+    fn any() -> Self {
+        if bool::any() {
+            Some(T::any())
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Arbitrary, const N: usize> Arbitrary for [T; N] {
+    fn any() -> Self {
+        core::array::from_fn(|_| T::any())
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Arbitrary),+> Arbitrary for ($($name,)+) {
+            fn any() -> Self {
+                ($($name::any(),)+)
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+
+fn env_u64(key: &str, default: u64) -> u64 {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| {
+            let v = v.trim();
+            match v.strip_prefix("0x") {
+                Some(hex) => u64::from_str_radix(hex, 16).ok(),
+                None => v.parse().ok(),
+            }
+        })
+        .unwrap_or(default)
+}
+
+fn install_quiet_hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let prev = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !info.payload().is::<Rejected>() {
+                prev(info);
+            }
+        }));
+    });
+}
+
+fn begin_sample(seed: u64) {
+    STATE.with(|s| s.set(seed));
+    TAPE.with(|t| t.borrow_mut().clear());
+    INPUTS.with(|i| i.borrow_mut().clear());
+}
+
+/// A failing sample found by [`find_counterexample`].
+pub struct Counterexample {
+    harness: &'static str,
+    seed: u64,
+    passed: u64,
+    tape: Vec<u64>,
+    inputs: Vec<(&'static str, String)>,
+    source: Option<&'static str>,
+    tiered: bool,
+    payload: Box<dyn Any + Send>,
+}
+
+impl Counterexample {
+    /// Seed that reproduces this sample with `KANI_SOLANA_CASES=1`.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Raw draws consumed by the failing sample, in order; pass to [`replay`].
+    pub fn tape(&self) -> &[u64] {
+        &self.tape
+    }
+
+    /// Concrete inputs the harness drew, as `(type, value)` in draw order.
+    pub fn inputs(&self) -> &[(&'static str, String)] {
+        &self.inputs
+    }
+
+    /// Attach the harness body, so [`regression_test`](Self::regression_test)
+    /// can replay a closure. [`check!`](crate::check) does this for you.
+    pub fn with_source(mut self, source: &'static str) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Mark this as a [`tiered_proof!`](crate::tiered_proof) instance, whose
+    /// closure sits in a generated `<proof>::width_*` module.
+    pub(crate) fn tiered(mut self) -> Self {
+        self.tiered = true;
+        self
+    }
+
+    /// Source of a `#[test]` that replays this sample, to be pasted into the
+    /// module that defines the harness.
+    ///
+    /// A named harness function is called by name, so it resolves from that
+    /// module even when private. A closure is replayed from the body recorded
+    /// by [`check!`](crate::check) or [`tiered_proof!`](crate::tiered_proof),
+    /// the latter pasted beside the `tiered_proof!`; a closure passed to
+    /// [`check`] has no recorded body, and the generated test fails to compile
+    /// rather than replaying nothing.
+    pub fn regression_test(&self) -> String {
+        let (path, is_closure) = match self.harness.find("::{{closure}}") {
+            Some(i) => (&self.harness[..i], true),
+            None => (self.harness, false),
+        };
+        let (module, item) = path.rsplit_once("::").unwrap_or(("", path));
+        let tiered_name;
+        let (module, item) = match module.rsplit_once("::") {
+            Some((parent, proof)) if self.tiered => {
+                tiered_name = format!("{proof}_{item}");
+                (parent, tiered_name.as_str())
+            }
+            _ => (module, item),
+        };
+        let module = match module.split_once("::") {
+            Some((_, rest)) => format!("crate::{rest}"),
+            None => "crate".to_string(),
+        };
+        let name = item.split('<').next().unwrap_or("harness");
+        let tape: Vec<String> = self.tape.iter().map(|v| format!("{v:#x}")).collect();
+
+        let mut out = String::new();
+        let _ = writeln!(out, "// Paste into `{module}`.");
+        let _ = writeln!(out, "#[test]");
+        let _ = writeln!(out, "fn {name}_regression_{:x}() {{", self.seed);
+        for (ty, value) in &self.inputs {
+            let _ = writeln!(out, "    // {ty} = {value}");
+        }
+        let target = match (is_closure, self.source) {
+            (false, _) => item.replacen('<', "::<", 1),
+            (true, Some(source)) => format!("|| {source}"),
+            (true, None) => {
+                let _ = writeln!(
+                    out,
+                    "    compile_error!(\"closure harness in `{name}` was not recorded; \
+                     rerun it with kani_solana::check! or pass a named fn to check\");"
+                );
+                "|| {}".to_string()
+            }
+        };
+        let _ = writeln!(
+            out,
+            "    kani_solana::runtime::replay(&[{}], {target});",
+            tape.join(", ")
+        );
+        out.push_str("}\n");
+        out
+    }
+
+    /// Human-readable summary: inputs, replay seed and regression test.
+    pub fn report(&self) -> String {
+        let mut out = format!(
+            "kani-solana runtime: counterexample after {} passing cases\n",
+            self.passed
+        );
+        for (ty, value) in &self.inputs {
+            let _ = writeln!(out, "  input: {ty} = {value}");
+        }
+        let _ = writeln!(
+            out,
+            "  replay: KANI_SOLANA_SEED={:#x} KANI_SOLANA_CASES=1",
+            self.seed
+        );
+        out.push_str("  regression test:\n\n");
+        out.push_str(&self.regression_test());
+        out
+    }
+
+    /// Re-raise the original assertion failure.
+    pub fn into_panic(self) -> ! {
+        panic::resume_unwind(self.payload)
+    }
+}
+
+/// Sample `harness` like [`check`], returning the first failing sample instead
+/// of panicking.
+///
+/// # Panics
+///
+/// Panics if the assumptions reject too many samples to reach the requested
+/// case count.
+pub fn find_counterexample<F>(harness: F) -> Option<Counterexample>
+where
+    F: Fn(),
+{
+    install_quiet_hook();
+    COVERS.with(|c| c.borrow_mut().clear());
+    let cases = env_u64("KANI_SOLANA_CASES", DEFAULT_CASES);
+    let seed = env_u64("KANI_SOLANA_SEED", DEFAULT_SEED);
+    let max_attempts = cases.saturating_mul(MAX_REJECTS_PER_CASE).max(1);
+
+    let mut accepted = 0;
+    let mut attempt = 0;
+    while accepted < cases {
+        assert!(
+            attempt < max_attempts,
+            "too many rejected samples: {accepted} of {cases} cases after {attempt} attempts; \
+             the assumptions are too narrow for random sampling"
+        );
+        let case_seed = seed.wrapping_add(attempt.wrapping_mul(GOLDEN_GAMMA));
+        begin_sample(case_seed);
+        attempt += 1;
+
+        match panic::catch_unwind(AssertUnwindSafe(&harness)) {
+            Ok(()) => accepted += 1,
+            Err(payload) if payload.is::<Rejected>() => {}
+            Err(payload) => {
+                return Some(Counterexample {
+                    harness: type_name::<F>(),
+                    seed: case_seed,
+                    passed: accepted,
+                    tape: TAPE.with(|t| t.take()),
+                    inputs: INPUTS.with(|i| i.take()),
+                    source: None,
+                    tiered: false,
+                    payload,
+                });
+            }
+        }
+    }
+
+    COVERS.with(|c| {
+        for (msg, hit) in c.borrow().iter() {
+            if !hit {
+                eprintln!("kani-solana runtime: cover never satisfied: {msg}");
+            }
+        }
+    });
+    None
+}
+
+/// Run `harness` against randomized inputs until `KANI_SOLANA_CASES` samples
+/// satisfy every [`assume`].
+///
+/// A failing sample is reported with its inputs and a regression test (see
+/// [`Counterexample::report`]), and any `cover!` that never held is reported
+/// as a warning.
+///
+/// # Panics
+///
+/// Re-raises the first assertion failure, or panics if the assumptions reject
+/// too many samples to reach the requested case count.
+pub fn check<F>(harness: F)
+where
+    F: Fn(),
+{
+    if let Some(cx) = find_counterexample(harness) {
+        eprintln!("{}", cx.report());
+        cx.into_panic();
+    }
+}
+
+/// [`check`] a harness given as a block body, recording its source so the
+/// regression test of a failure replays the same body.
+///
+/// ```ignore
+/// kani_solana::check!({
+///     let total: u64 = kani_solana::backend::any();
+///     assert_eq!(total / 2 + total / 2, total);
+/// });
+/// ```
+#[macro_export]
+macro_rules! check {
+    ($body:block) => {
+        if let Some(cx) = $crate::runtime::find_counterexample(|| $body) {
+            let cx = cx.with_source(stringify!($body));
+            eprintln!("{}", cx.report());
+            cx.into_panic();
+        }
+    };
+}
+
+/// Run `harness` once, feeding it the recorded draws in `tape`.
+///
+/// This is what generated regression tests call. Draws beyond the end of the
+/// tape fall back to the default seed.
+///
+/// # Panics
+///
+/// Propagates the harness failure, or panics if the replayed inputs are now
+/// rejected by the harness assumptions.
+pub fn replay<F>(tape: &[u64], harness: F)
+where
+    F: Fn(),
+{
+    install_quiet_hook();
+    begin_sample(DEFAULT_SEED);
+    SCRIPT.with(|s| {
+        let mut script = s.borrow_mut();
+        script.clear();
+        script.extend(tape.iter().rev());
+    });
+    let result = panic::catch_unwind(AssertUnwindSafe(&harness));
+    SCRIPT.with(|s| s.borrow_mut().clear());
+    if let Err(payload) = result {
+        if payload.is::<Rejected>() {
+            panic!("replayed inputs no longer satisfy the harness assumptions");
+        }
+        panic::resume_unwind(payload);
+    }
+}
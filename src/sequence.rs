@@ -0,0 +1,115 @@
+//! Proof helpers for bounded sequences of state transitions.
+//!
+//! Single-call helpers miss bugs that only appear after several instructions
+//! (deposit, trade, crank, withdraw). These helpers draw `K` arbitrary
+//! operations, apply them in order to a user-supplied state and check an
+//! invariant after every step, so every sequence of length `1..=K` is covered.
+//!
+//! Under Kani the harness calling these needs `#[kani::unwind(K + 1)]` or a
+//! larger bound if `apply` itself loops.
+
+use crate::backend::{self, Arbitrary};
+
+/// Proves that `invariant` holds for the initial state and after every step of
+/// every sequence of up to `K` operations.
+///
+/// `init` builds the starting state (it may draw symbolic values), `apply`
+/// performs one operation in place. On failure the assertion reports the
+/// operations applied so far; under Kani the same trace is in the CBMC output.
+pub fn assert_invariant_over_sequences<const K: usize, S, Op, G, A, I>(
+    init: G,
+    apply: A,
+    invariant: I,
+) where
+    Op: Arbitrary,
+    G: FnOnce() -> S,
+    A: Fn(&mut S, &Op),
+    I: Fn(&S) -> bool,
+{
+    let mut state = init();
+    assert!(invariant(&state), "invariant violated by initial state");
+
+    let ops: [Op; K] = backend::any();
+    let mut step = 0;
+    while step < K {
+        apply(&mut state, &ops[step]);
+        if !invariant(&state) {
+            invariant_violated(&ops[..=step]);
+        }
+        step += 1;
+    }
+}
+
+/// Like [`assert_invariant_over_sequences`], but `apply` may reject an
+/// operation by returning `Err`, in which case the state must be unchanged.
+///
+/// Models instructions that fail atomically: the invariant is still checked
+/// after a rejected operation, and the sequence continues.
+pub fn assert_invariant_over_fallible_sequences<const K: usize, S, Op, E, G, A, I>(
+    init: G,
+    apply: A,
+    invariant: I,
+) where
+    S: Clone + PartialEq,
+    Op: Arbitrary,
+    G: FnOnce() -> S,
+    A: Fn(&mut S, &Op) -> Result<(), E>,
+    I: Fn(&S) -> bool,
+{
+    let mut state = init();
+    assert!(invariant(&state), "invariant violated by initial state");
+
+    let ops: [Op; K] = backend::any();
+    let mut step = 0;
+    while step < K {
+        let before = state.clone();
+        if apply(&mut state, &ops[step]).is_err() {
+            assert!(state == before, "rejected operation modified state");
+        }
+        if !invariant(&state) {
+            invariant_violated(&ops[..=step]);
+        }
+        step += 1;
+    }
+}
+
+/// Proves that `step_ok(before, op, after)` holds for every step of every
+/// sequence of up to `K` operations.
+///
+/// For two-state properties that a single-state invariant cannot express,
+/// e.g. "an operation on account `i` never reduces another account's balance".
+/// Rejected operations (`Err`) are checked like successful ones.
+pub fn assert_transition_over_sequences<const K: usize, S, Op, E, G, A, P>(
+    init: G,
+    apply: A,
+    step_ok: P,
+) where
+    S: Clone,
+    Op: Arbitrary,
+    G: FnOnce() -> S,
+    A: Fn(&mut S, &Op) -> Result<(), E>,
+    P: Fn(&S, &Op, &S) -> bool,
+{
+    let mut state = init();
+
+    let ops: [Op; K] = backend::any();
+    let mut step = 0;
+    while step < K {
+        let before = state.clone();
+        let _ = apply(&mut state, &ops[step]);
+        if !step_ok(&before, &ops[step], &state) {
+            invariant_violated(&ops[..=step]);
+        }
+        step += 1;
+    }
+}
+
+#[cfg(kani)]
+fn invariant_violated<Op>(_trace: &[Op]) -> ! {
+    panic!("invariant violated after operation sequence")
+}
+
+#[cfg(not(kani))]
+fn invariant_violated<Op: core::fmt::Debug>(trace: &[Op]) -> ! {
+    panic!("invariant violated after operation sequence: {trace:?}")
+}
@@ -0,0 +1,189 @@
+//! Proof tiers and bit-width instantiation.
+//!
+//! A harness written once with [`tiered_proof!`](crate::tiered_proof) is
+//! instantiated at `u8`, `u16`, `u64` and `u128` input widths. Which widths
+//! actually run depends on the active tier:
+//!
+//! | tier         | widths                    | select with                                         |
+//! |--------------|---------------------------|-----------------------------------------------------|
+//! | `smoke`      | `u8`, `u16`               | default                                             |
+//! | `full`       | `u8`, `u16`, `u64`        | feature `kani-full` or `KANI_SOLANA_TIER=full`      |
+//! | `exhaustive` | all, including `u128`     | feature `kani-exhaustive` or `KANI_SOLANA_TIER=exhaustive` |
+//!
+//! `KANI_SOLANA_TIER` is read when kani-solana is compiled; the higher of the
+//! feature and environment selection wins. Instances above the active tier
+//! are compiled out, so they are neither run nor reported as passing.
+
+use crate::backend;
+
+/// Input width a tiered harness can be instantiated at.
+///
+/// Implemented for `u8`, `u16`, `u64` and `u128`. Values are drawn at the
+/// width and widened, so harness bodies can work in `u128`/`i128` throughout.
+pub trait Width {
+    /// Unsigned value of this width, widened to `u128`.
+    fn any_unsigned() -> u128;
+    /// Signed value of this width, widened to `i128`.
+    fn any_signed() -> i128;
+}
+
+macro_rules! impl_width {
+    ($($u:ty, $i:ty),* $(,)?) => {$(
+        impl Width for $u {
+            fn any_unsigned() -> u128 {
+                backend::any::<$u>() as u128
+            }
+
+            fn any_signed() -> i128 {
+                backend::any::<$i>() as i128
+            }
+        }
+    )*};
+}
+
+impl_width!(u8, i8, u16, i16, u64, i64, u128, i128);
+
+/// Unsigned value of width `W`, widened to `u128`.
+pub fn any_unsigned<W: Width>() -> u128 {
+    W::any_unsigned()
+}
+
+/// Signed value of width `W`, widened to `i128`.
+pub fn any_signed<W: Width>() -> i128 {
+    W::any_signed()
+}
+
+/// Run one width instance of a tiered harness whose body is `source`.
+///
+/// Under Kani the body runs once symbolically; with the runtime backend it is
+/// sampled like [`check!`](crate::check), and a failure prints a regression
+/// test that replays `source` at the same width.
+#[doc(hidden)]
+pub fn run<F: Fn()>(source: &'static str, harness: F) {
+    #[cfg(kani)]
+    {
+        let _ = source;
+        harness();
+    }
+    #[cfg(not(kani))]
+    if let Some(cx) = crate::runtime::find_counterexample(harness) {
+        let cx = cx.with_source(source).tiered();
+        eprintln!("{}", cx.report());
+        cx.into_panic();
+    }
+}
+
+/// Define a harness once and instantiate it at every width in [`Width`].
+///
+/// Expands to a module named after the harness with one `#[kani::proof]`
+/// (or `#[test]` under the runtime backend) per width enabled in the active
+/// tier: `u8` and `u16` always, `u64` from `full`, `u128` in
+/// `exhaustive`. Inside the body the type parameter names the width; draw inputs
+/// with [`any_unsigned`] / [`any_signed`].
+///
+/// ```ignore
+/// kani_solana::tiered_proof! {
+///     fn fee_never_exceeds_amount<W>() {
+///         let amount = kani_solana::tier::any_unsigned::<W>();
+///         assert!(fee(amount) <= amount);
+///     }
+/// }
+/// ```
+///
+/// Writing the parameter as `<W: u64>` stops at `u64`, for bodies whose
+/// inputs are `u64` anyway and would only repeat the `u64` instance in
+/// `exhaustive`.
+///
+/// Extra attributes are copied onto every instance; wrap Kani-only ones
+/// such as `unwind` in `cfg_attr(kani, ...)`. Instances are named
+/// `<harness>::width_u8` and so on, and are visible to the enclosing module,
+/// so `cargo kani --harness <harness>::width_u16` selects one and a concrete
+/// playback test pasted beside the macro can call it.
+#[macro_export]
+macro_rules! tiered_proof {
+    ($(#[$meta:meta])* fn $name:ident<$w:ident>() $body:block) => {
+        $crate::tiered_proof!(@module [$(#[$meta])*] $name $w $body [u128]);
+    };
+    ($(#[$meta:meta])* fn $name:ident<$w:ident: u64>() $body:block) => {
+        $crate::tiered_proof!(@module [$(#[$meta])*] $name $w $body []);
+    };
+    (@module $attrs:tt $name:ident $w:ident $body:block [$($wide:ident)?]) => {
+        #[allow(non_snake_case)]
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $crate::tiered_proof!(@instance $attrs $w $body width_u8 u8);
+            $crate::tiered_proof!(@instance $attrs $w $body width_u16 u16);
+            $crate::__tier_full! {
+                $crate::tiered_proof!(@instance $attrs $w $body width_u64 u64);
+            }
+            $($crate::__tier_exhaustive! {
+                $crate::tiered_proof!(@instance $attrs $w $body width_u128 $wide);
+            })?
+        }
+    };
+    (@instance [$(#[$meta:meta])*] $w:ident $body:block $fn_name:ident $ty:ty) => {
+        #[cfg_attr(kani, kani::proof)]
+        #[cfg_attr(not(kani), test)]
+        $(#[$meta])*
+        pub(super) fn $fn_name() {
+            $crate::tier::run(
+                concat!(
+                    "{ #[allow(dead_code)] type ",
+                    stringify!($w),
+                    " = ",
+                    stringify!($ty),
+                    "; ",
+                    stringify!($body),
+                    " }"
+                ),
+                || {
+                    #[allow(dead_code)]
+                    type $w = $ty;
+                    $body
+                },
+            );
+        }
+    };
+}
+
+/// Emits its items only when the `full` tier is active.
+#[cfg(any(
+    feature = "kani-full",
+    kani_solana_tier = "full",
+    kani_solana_tier = "exhaustive"
+))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tier_full {
+    ($($item:item)*) => { $($item)* };
+}
+
+/// Emits its items only when the `full` tier is active.
+#[cfg(not(any(
+    feature = "kani-full",
+    kani_solana_tier = "full",
+    kani_solana_tier = "exhaustive"
+)))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tier_full {
+    ($($item:item)*) => {};
+}
+
+/// Emits its items only when the `exhaustive` tier is active.
+#[cfg(any(feature = "kani-exhaustive", kani_solana_tier = "exhaustive"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tier_exhaustive {
+    ($($item:item)*) => { $($item)* };
+}
+
+/// Emits its items only when the `exhaustive` tier is active.
+#[cfg(not(any(feature = "kani-exhaustive", kani_solana_tier = "exhaustive")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tier_exhaustive {
+    ($($item:item)*) => {};
+}
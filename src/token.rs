@@ -1,11 +1,13 @@
 //! Proof helpers for value-conserving splits.
 
+use crate::backend;
+
 /// Proves that `split_fn(total)` conserves value for all symbolic totals.
 pub fn assert_two_way_split_conserves<F>(split_fn: F)
 where
     F: Fn(u64) -> (u64, u64),
 {
-    let total: u64 = kani::any();
+    let total: u64 = backend::any();
     let (a, b) = split_fn(total);
     assert!(a <= total, "part_a exceeds total");
     assert!(b <= total, "part_b exceeds total");
@@ -18,7 +20,7 @@ pub fn assert_three_way_split_conserves<F>(split_fn: F)
 where
     F: Fn(u64) -> (u64, u64, u64),
 {
-    let total: u64 = kani::any();
+    let total: u64 = backend::any();
     let (a, b, c) = split_fn(total);
     assert!(a <= total, "part_a exceeds total");
     assert!(b <= total, "part_b exceeds total");
@@ -32,12 +34,99 @@ pub fn assert_bps_split_conserves<F>(split_fn: F)
 where
     F: Fn(u64, u64) -> (u64, u64),
 {
-    let total: u64 = kani::any();
-    let rate_bps: u64 = kani::any();
-    kani::assume(rate_bps <= 10_000);
+    let total: u64 = backend::any();
+    let rate_bps: u64 = backend::any();
+    backend::assume(rate_bps <= 10_000);
     let (a, b) = split_fn(total, rate_bps);
     assert!(a <= total, "part_a exceeds total");
     assert!(b <= total, "part_b exceeds total");
     let sum = (a as u128) + (b as u128);
     assert_eq!(sum, total as u128, "bps split does not conserve value");
 }
+
+/// Proves that `split_fn(total)` conserves value across `N` parts for all symbolic totals.
+pub fn assert_n_way_split_conserves<const N: usize, F>(split_fn: F)
+where
+    F: Fn(u64) -> [u64; N],
+{
+    let total: u64 = backend::any();
+    let parts = split_fn(total);
+    let mut wide = [0u128; N];
+    let mut i = 0;
+    while i < N {
+        wide[i] = parts[i] as u128;
+        i += 1;
+    }
+    assert_parts_conserve(&wide, total as u128);
+}
+
+/// `u128` variant of [`assert_n_way_split_conserves`].
+pub fn assert_n_way_split_conserves_u128<const N: usize, F>(split_fn: F)
+where
+    F: Fn(u128) -> [u128; N],
+{
+    let total: u128 = backend::any();
+    let parts = split_fn(total);
+    assert_parts_conserve(&parts, total);
+}
+
+/// Proves that `split_fn(total, weights_bps)` conserves value for all symbolic
+/// totals and all weight vectors whose entries sum to exactly `10_000`.
+pub fn assert_bps_weighted_split_conserves<const N: usize, F>(split_fn: F)
+where
+    F: Fn(u64, &[u64; N]) -> [u64; N],
+{
+    let total: u64 = backend::any();
+    let weights = any_bps_weights::<N>();
+    let parts = split_fn(total, &weights);
+    let mut wide = [0u128; N];
+    let mut i = 0;
+    while i < N {
+        wide[i] = parts[i] as u128;
+        i += 1;
+    }
+    assert_parts_conserve(&wide, total as u128);
+}
+
+/// `u128` variant of [`assert_bps_weighted_split_conserves`].
+pub fn assert_bps_weighted_split_conserves_u128<const N: usize, F>(split_fn: F)
+where
+    F: Fn(u128, &[u64; N]) -> [u128; N],
+{
+    let total: u128 = backend::any();
+    let weights = any_bps_weights::<N>();
+    let parts = split_fn(total, &weights);
+    assert_parts_conserve(&parts, total);
+}
+
+/// `N` symbolic basis-point weights summing to exactly `10_000`.
+///
+/// Built left to right from the remaining budget, so every such vector is
+/// reachable without an equality assumption.
+fn any_bps_weights<const N: usize>() -> [u64; N] {
+    backend::assume(N > 0);
+    let mut weights = [0u64; N];
+    let mut remaining: u64 = 10_000;
+    let mut i = 0;
+    while i + 1 < N {
+        weights[i] = backend::any_in_range(0, remaining);
+        remaining -= weights[i];
+        i += 1;
+    }
+    weights[N - 1] = remaining;
+    weights
+}
+
+fn assert_parts_conserve(parts: &[u128], total: u128) {
+    let mut sum: u128 = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        assert!(parts[i] <= total, "part exceeds total");
+        sum = match sum.checked_add(parts[i]) {
+            Some(s) => s,
+            None => panic!("split does not conserve value"),
+        };
+        i += 1;
+    }
+    assert_eq!(sum, total, "split does not conserve value");
+}
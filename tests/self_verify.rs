@@ -3,7 +3,8 @@
 
 #[cfg(kani)]
 mod self_proofs {
-    use kani_solana::{generators::*, math::*, token::*};
+    use kani_solana::risk::{haircut_ratio, loss_writeoff};
+    use kani_solana::{amm::*, bounds::*, generators::*, math::*, token::*};
 
     #[kani::proof]
     fn any_score_is_bounded() {
@@ -50,6 +51,14 @@ mod self_proofs {
         });
     }
 
+    #[kani::proof]
+    fn five_way_remainder_split_conserves() {
+        assert_n_way_split_conserves::<5, _>(|total| {
+            let share = total / 5;
+            [share, share, share, share, total - 4 * share]
+        });
+    }
+
     #[kani::proof]
     fn ceiling_avg_single_element_returns_score() {
         let score = any_score();
@@ -75,4 +84,866 @@ mod self_proofs {
     fn identity_u64_is_monotonic() {
         assert_monotonic_u64(|x| x);
     }
+
+    #[derive(kani::Arbitrary)]
+    enum CounterOp {
+        Inc,
+        Dec,
+    }
+
+    #[kani::proof]
+    #[kani::unwind(5)]
+    fn saturating_counter_stays_within_depth() {
+        kani_solana::sequence::assert_invariant_over_sequences::<4, _, CounterOp, _, _, _>(
+            || 0u8,
+            |count, op| match op {
+                CounterOp::Inc => *count += 1,
+                CounterOp::Dec => *count = count.saturating_sub(1),
+            },
+            |count| *count <= 4,
+        );
+    }
+
+    #[kani::proof]
+    fn u64_midpoint_matches_u128_reference() {
+        assert_equivalent(
+            |&(a, b): &(u64, u64)| ((a as u128 + b as u128) / 2) as u64,
+            |&(a, b)| a / 2 + b / 2 + (a & b & 1),
+        );
+    }
+
+    #[kani::proof]
+    fn writeoff_remaining_insurance_is_bounded() {
+        let insurance: u128 = kani::any::<u64>() as u128;
+        let neg_equity: u128 = kani::any::<u64>() as u128;
+        assert_bounded(|| loss_writeoff(neg_equity, insurance).1, 0, insurance);
+    }
+
+    #[kani::proof]
+    fn bps_fee_is_bounded_by_amount() {
+        assert_output_bounded_by(
+            |&(amount, bps)| ((amount as u128) * (bps as u128) / 10_000) as u64,
+            || (kani::any::<u64>(), any_bps()),
+            |_| 0,
+            |&(amount, _)| amount,
+        );
+    }
+
+    #[kani::proof]
+    fn haircut_is_one_without_profit() {
+        assert_default_on_condition_by_any(
+            |&(vault, principal, insurance, pnl_pos_total): &(u64, u64, u64, u64)| {
+                let (num, den) = haircut_ratio(
+                    vault as u128,
+                    principal as u128,
+                    insurance as u128,
+                    pnl_pos_total as u128,
+                );
+                (num, den)
+            },
+            |&(.., pnl_pos_total)| pnl_pos_total == 0,
+            (1, 1),
+        );
+    }
+
+    kani_solana::tiered_proof! {
+        fn reference_exact_in_swap_preserves_k<W: u64>() {
+            assert_cp_swap_preserves_k::<W, _>(swap_exact_in);
+        }
+    }
+
+    kani_solana::tiered_proof! {
+        fn reference_exact_out_swap_rounds_up<W: u64>() {
+            assert_cp_swap_exact_out_rounds_up::<W, _>(swap_exact_out);
+        }
+    }
+}
+
+/// The same helpers driven by the randomized runtime backend under `cargo test`.
+#[cfg(all(not(kani), feature = "runtime"))]
+mod runtime_checks {
+    use kani_solana::adl::{adl_allocate, adl_score, uncovered_deficit};
+    use kani_solana::backend::{any, assume};
+    use kani_solana::funding::{funding_rate, FundingIndex};
+    use kani_solana::risk::{
+        account_equity, checked_effective_pnl, checked_funding_payment, effective_pnl,
+        funding_payment, haircut_ratio, is_liquidatable, liquidation_price, match_fill,
+        position_notional, socialize_loss, socialize_pro_rata, unrealized_pnl, FeeSchedule,
+        FeeTier, Position, RiskError, TradeAccount, WarmupState,
+    };
+    use kani_solana::tier::any_unsigned;
+    use kani_solana::{amm::*, bounds::*, generators::*, math::*, runtime::*, token::*};
+
+    #[test]
+    fn any_score_is_bounded() {
+        check(|| assert!(any_score() <= 100));
+    }
+
+    #[test]
+    fn any_weight_is_positive_and_bounded() {
+        check(|| {
+            let w = any_weight();
+            assert!((1..=10_000).contains(&w));
+        });
+    }
+
+    #[test]
+    fn any_ordered_timestamps_are_ordered() {
+        check(|| {
+            let (t1, t2) = any_ordered_timestamps();
+            assert!(t1 <= t2);
+        });
+    }
+
+    #[test]
+    fn floor_bps_split_conserves() {
+        check(|| {
+            assert_bps_split_conserves(|total, rate| {
+                let burn = ((total as u128) * (rate as u128) / 10_000) as u64;
+                (burn, total - burn)
+            })
+        });
+    }
+
+    #[test]
+    fn five_way_weighted_split_conserves() {
+        check(|| {
+            assert_bps_weighted_split_conserves_u128::<5, _>(|total, weights| {
+                let mut parts = [0u128; 5];
+                let mut paid = 0;
+                for i in 0..4 {
+                    parts[i] = total / 10_000 * weights[i] as u128
+                        + total % 10_000 * weights[i] as u128 / 10_000;
+                    paid += parts[i];
+                }
+                parts[4] = total - paid;
+                parts
+            })
+        });
+    }
+
+    #[test]
+    fn ceiling_avg_bounded_at_cap() {
+        check(|| {
+            let pairs = [(any_score(), any_weight()), (any_score(), any_weight())];
+            assert!(ceiling_weighted_average(&pairs, 100) <= 100);
+        });
+    }
+
+    #[test]
+    fn identity_u64_is_monotonic() {
+        check(|| assert_monotonic_u64(|x| x));
+    }
+
+    #[test]
+    fn haircut_payout_is_bounded_by_profit() {
+        check(|| {
+            let vault: u128 = any();
+            let principal_total: u128 = any();
+            let insurance: u128 = any();
+            let pnl_pos_total: u128 = any();
+            let my_pnl: i128 = any();
+
+            let (h_num, h_den) = haircut_ratio(vault, principal_total, insurance, pnl_pos_total);
+            let payout = effective_pnl(my_pnl, h_num, h_den);
+            assert!(payout <= my_pnl.max(0) as u128);
+        });
+    }
+
+    #[test]
+    fn funding_payment_is_bounded_by_position() {
+        check(|| {
+            let position: u128 = any::<u64>() as u128;
+            let rate_den: u128 = any::<u64>() as u128;
+            let rate_num: i128 = any::<i64>() as i128;
+            assume(rate_den > 0 && rate_num.unsigned_abs() <= rate_den);
+            let is_long: bool = any();
+
+            let bound = position as i128;
+            assert_bounded(
+                || funding_payment(position, rate_num, rate_den, is_long),
+                -bound,
+                bound,
+            );
+        });
+    }
+
+    #[test]
+    fn readme_payout_is_bounded_by_positive_pnl() {
+        check(|| {
+            assert_output_bounded_by_any(
+                |&(vault, principal, insurance, pnl_pos_total, pnl): &(
+                    u128,
+                    u128,
+                    u128,
+                    u128,
+                    i128,
+                )| {
+                    let (h_num, h_den) = haircut_ratio(vault, principal, insurance, pnl_pos_total);
+                    effective_pnl(pnl, h_num, h_den)
+                },
+                |_| 0,
+                |&(.., pnl)| pnl.max(0) as u128,
+            )
+        });
+    }
+
+    #[test]
+    fn funding_is_zero_without_position() {
+        check(|| {
+            assert_default_on_condition_by(
+                |&(position, rate_num, rate_den, is_long)| {
+                    funding_payment(position, rate_num, rate_den, is_long)
+                },
+                || {
+                    let position: u128 = if any() { 0 } else { any() };
+                    (position, any::<i128>(), any::<u128>(), any::<bool>())
+                },
+                |&(position, ..)| position == 0,
+                0,
+            )
+        });
+    }
+
+    enum MulError {
+        Overflow,
+        ZeroDivisor,
+    }
+
+    fn mul_div_u128(&(x, y, d): &(u64, u64, u64)) -> Result<u64, MulError> {
+        if d == 0 {
+            return Err(MulError::ZeroDivisor);
+        }
+        u64::try_from(x as u128 * y as u128 / d as u128).map_err(|_| MulError::Overflow)
+    }
+
+    fn mul_div_checked(&(x, y, d): &(u64, u64, u64)) -> Result<u64, MulError> {
+        if d == 0 {
+            return Err(MulError::ZeroDivisor);
+        }
+        match x.checked_mul(y) {
+            Some(p) => Ok(p / d),
+            None => Err(MulError::Overflow),
+        }
+    }
+
+    #[test]
+    fn checked_mul_div_matches_wide_reference_below_overflow() {
+        check(|| {
+            assert_equivalent_result_when(mul_div_u128, mul_div_checked, |&(x, y, _)| {
+                x.checked_mul(y).is_some()
+            })
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "implementations disagree on Ok vs Err")]
+    fn checked_mul_div_rejects_more_than_wide_reference() {
+        check(|| assert_equivalent_result_when(mul_div_u128, mul_div_checked, |_| true));
+    }
+
+    #[derive(Debug)]
+    enum VaultOp {
+        Deposit(u8),
+        Withdraw(u8),
+    }
+
+    impl kani_solana::backend::Arbitrary for VaultOp {
+        fn any() -> Self {
+            if any() {
+                VaultOp::Deposit(any())
+            } else {
+                VaultOp::Withdraw(any())
+            }
+        }
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct Vault {
+        balance: u32,
+        deposited: u32,
+        withdrawn: u32,
+    }
+
+    fn apply_vault_op(vault: &mut Vault, op: &VaultOp) -> Result<(), ()> {
+        match *op {
+            VaultOp::Deposit(amount) => {
+                vault.balance += amount as u32;
+                vault.deposited += amount as u32;
+            }
+            VaultOp::Withdraw(amount) => {
+                vault.balance = vault.balance.checked_sub(amount as u32).ok_or(())?;
+                vault.withdrawn += amount as u32;
+            }
+        }
+        Ok(())
+    }
+
+    fn vault_is_conserved(vault: &Vault) -> bool {
+        vault.balance + vault.withdrawn == vault.deposited
+    }
+
+    #[test]
+    fn vault_sequences_conserve_balance() {
+        check(|| {
+            kani_solana::sequence::assert_invariant_over_fallible_sequences::<
+                8,
+                _,
+                VaultOp,
+                _,
+                _,
+                _,
+                _,
+            >(
+                || Vault {
+                    balance: 0,
+                    deposited: 0,
+                    withdrawn: 0,
+                },
+                apply_vault_op,
+                vault_is_conserved,
+            )
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "invariant violated after operation sequence")]
+    fn vault_bug_reports_trace() {
+        check(|| {
+            kani_solana::sequence::assert_invariant_over_sequences::<8, _, VaultOp, _, _, _>(
+                || Vault {
+                    balance: 0,
+                    deposited: 0,
+                    withdrawn: 0,
+                },
+                |vault, op| {
+                    if let VaultOp::Withdraw(amount) = *op {
+                        // Bug: pays out without debiting the balance.
+                        vault.withdrawn += amount as u32;
+                    }
+                },
+                vault_is_conserved,
+            )
+        });
+    }
+
+    kani_solana::tiered_proof! {
+        fn fee_sweep_conserves_at_every_width<W>() {
+            let debt = any_unsigned::<W>();
+            let available = any_unsigned::<W>();
+            let (swept, remaining) = kani_solana::risk::fee_debt_sweep(debt, available);
+            assert_eq!(swept + remaining, debt);
+        }
+    }
+
+    #[test]
+    fn engine_conserves_value() {
+        check(kani_solana::engine::assert_engine_conserves_value::<3, 8>);
+    }
+
+    #[test]
+    fn engine_protects_principal() {
+        check(kani_solana::engine::assert_engine_protects_principal::<3, 8>);
+    }
+
+    #[test]
+    fn losing_account_with_capital_is_not_liquidated() {
+        use kani_solana::engine::{Engine, EngineError};
+
+        let mut engine = Engine::<2>::new();
+        engine.deposit(0, 100).unwrap();
+        engine.deposit(1, 100).unwrap();
+        engine.settle_trade(1, 0, 1).unwrap();
+        assert_eq!(engine.liquidate(0), Err(EngineError::NotLiquidatable));
+
+        engine.settle_trade(1, 0, 200).unwrap();
+        assert!(engine.equity(0).unwrap() < 0);
+        assert_eq!(engine.liquidate(0), Ok(()));
+        assert!(engine.is_solvent());
+    }
+
+    fn lossy_half_split() {
+        assert_two_way_split_conserves(|total| (total / 2, total / 2));
+    }
+
+    #[test]
+    fn long_liquidation_is_monotone_in_falling_price() {
+        check(|| {
+            let size = any::<u32>() as i128;
+            let entry = any::<u32>() as u128;
+            let (p1, p2) = (any::<u32>() as u128, any::<u32>() as u128);
+            let capital = any::<u64>() as u128;
+            let mm_bps = any_bps();
+            assume(p2 <= p1);
+
+            let liq_at = |price| {
+                let equity = account_equity(capital, unrealized_pnl(size, entry, price), 1, 1, 0);
+                is_liquidatable(equity, position_notional(size, price), mm_bps)
+            };
+            assert!(!liq_at(p1) || liq_at(p2));
+        });
+    }
+
+    #[test]
+    fn liquidation_price_matches_on_chain_check() {
+        check(|| {
+            let size = any::<i16>() as i128;
+            let entry = any::<u16>() as u128;
+            let capital = any::<u32>() as u128;
+            let mm_bps = any_bps();
+
+            let liq_at = |price| {
+                let equity = account_equity(capital, unrealized_pnl(size, entry, price), 1, 1, 0);
+                is_liquidatable(equity, position_notional(size, price), mm_bps)
+            };
+            let price = any::<u32>() as u128;
+            match liquidation_price(size, entry, capital, mm_bps) {
+                Some(liq) if size > 0 => {
+                    assert_eq!(liq_at(price), price <= liq);
+                    assert!(liq_at(liq) && (liq == u128::MAX || !liq_at(liq + 1)));
+                }
+                Some(liq) => {
+                    assert_eq!(liq_at(price), price >= liq);
+                    assert!(liq_at(liq) && !liq_at(liq - 1));
+                }
+                None => assert!(!liq_at(price)),
+            }
+        });
+    }
+
+    #[test]
+    fn adl_covers_deficit_without_negative_profit() {
+        check(|| {
+            let pnl: [i128; 5] = any::<[i16; 5]>().map(i128::from);
+            let notional: [u32; 5] = any();
+            let capital: [u16; 5] = any();
+            let scores: [u128; 5] = core::array::from_fn(|i| {
+                let equity = account_equity(capital[i] as u128, pnl[i], 1, 1, 0);
+                adl_score(pnl[i], notional[i] as u128, equity)
+            });
+            let deficit = uncovered_deficit(any::<u32>() as u128, any::<u16>() as u128);
+
+            let fill = adl_allocate::<5, 3>(&pnl, &scores, deficit);
+            assert_eq!(fill.total() + fill.unfilled, deficit);
+            for (taken, pnl) in fill.deleveraged.iter().zip(pnl) {
+                assert!(*taken <= pnl.max(0) as u128);
+            }
+        });
+    }
+
+    #[test]
+    fn funding_settlement_nets_to_zero() {
+        check(|| {
+            let sizes: [i128; 5] = any::<[i16; 5]>().map(i128::from);
+            let long_oi: u128 = sizes
+                .iter()
+                .filter(|s| **s > 0)
+                .map(|s| s.unsigned_abs())
+                .sum();
+            let short_oi: u128 = sizes
+                .iter()
+                .filter(|s| **s < 0)
+                .map(|s| s.unsigned_abs())
+                .sum();
+            let rate = funding_rate(
+                any::<u32>() as u128,
+                any::<u32>() as u128,
+                any::<i16>() as i128,
+                5_000,
+            );
+            let start = FundingIndex {
+                long: any::<i64>() as i128,
+                short: any::<i64>() as i128,
+            };
+
+            let end = start
+                .crank(rate, any::<u32>() as u128, long_oi, short_oi)
+                .expect("small operands never overflow");
+            let net: i128 = sizes.iter().map(|s| end.settle(*s, &start)).sum();
+            assert!((0..=5).contains(&net));
+        });
+    }
+
+    #[test]
+    fn checked_funding_payment_matches_saturating_when_ok() {
+        check(|| {
+            let (position, rate_num, rate_den, is_long) = any::<(u128, i128, u64, bool)>();
+            if let Ok(p) = checked_funding_payment(position, rate_num, rate_den as u128, is_long) {
+                assert_eq!(
+                    p,
+                    funding_payment(position, rate_num, rate_den as u128, is_long)
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn checked_effective_pnl_matches_unchecked_when_ok() {
+        check(|| {
+            let (pnl, h_num, h_den, full) = any::<(i128, u128, u128, bool)>();
+            let h_num = if full { h_den } else { h_num };
+            match checked_effective_pnl(pnl, h_num, h_den) {
+                Ok(y) => assert_eq!(y, effective_pnl(pnl, h_num, h_den)),
+                Err(RiskError::Overflow) => assert!(h_num < h_den),
+                Err(_) => assert!(h_den == 0 || h_num > h_den),
+            }
+        });
+        assert_eq!(
+            checked_effective_pnl(i128::MAX, u128::MAX, u128::MAX),
+            Ok(i128::MAX as u128)
+        );
+    }
+
+    #[test]
+    fn position_pnl_matches_cash_flow() {
+        check(|| {
+            let fills: [(i16, u16); 4] = any();
+            let mark = any::<u16>() as u128;
+            let mut position = Position::default();
+            let (mut realized, mut cash, mut slack) = (0i128, 0i128, 0i128);
+            for (delta, price) in fills {
+                let (next, r) = position.fill(delta.into(), price.into()).unwrap();
+                position = next;
+                realized += r;
+                cash -= i128::from(delta) * i128::from(price);
+                slack += position.size.abs();
+            }
+
+            let exact = cash + position.size * mark as i128;
+            let total = realized + position.unrealized_pnl(mark);
+            assert!(total <= exact && exact - total <= slack);
+        });
+    }
+
+    /// Warmup ledger plus ghost totals: warmed profit before each reset and
+    /// total withdrawn.
+    type WarmupLedger = (WarmupState, u128, u128);
+
+    fn apply_warmup_op(ledger: &mut WarmupLedger, &(kind, amount, haircut): &(u8, u16, bool)) {
+        let (state, warmed_before_resets, withdrawn) = ledger;
+        let amount = amount as u128;
+        match kind % 3 {
+            0 => state.accrue(amount as u64, u128::from(!haircut), 1),
+            1 => *withdrawn += state.withdraw(amount),
+            _ => {
+                let warmed = state.warmed();
+                state.on_new_profit(amount).unwrap();
+                *warmed_before_resets += warmed;
+            }
+        }
+    }
+
+    #[test]
+    fn warmup_withdrawals_never_exceed_warmed_profit() {
+        check(|| {
+            kani_solana::sequence::assert_invariant_over_sequences::<8, _, (u8, u16, bool), _, _, _>(
+                || (WarmupState::new(any::<u8>() as u64), 0, 0),
+                apply_warmup_op,
+                |(state, warmed_before_resets, withdrawn)| {
+                    *withdrawn <= warmed_before_resets + state.warmed()
+                },
+            )
+        });
+    }
+
+    #[test]
+    fn socialized_loss_conserves_value() {
+        check(|| {
+            let (negative_equity, insurance, pnl_pos_total) = any::<(u128, u128, u128)>();
+            let s = socialize_loss(negative_equity, insurance, pnl_pos_total);
+            assert_eq!(s.covered + s.socialized + s.unabsorbed, negative_equity);
+            assert_eq!(s.insurance + s.covered, insurance);
+            assert_eq!(s.pnl_pos_total + s.socialized, pnl_pos_total);
+        });
+    }
+
+    #[test]
+    fn socialized_loss_keeps_payouts_within_residual() {
+        check(|| {
+            let (vault, principal_total, insurance, negative_equity) =
+                any::<(u64, u64, u64, u64)>();
+            let mut pnl = any::<[i64; 4]>().map(i128::from);
+            let pnl_pos_total: u128 = pnl.iter().map(|&p| p.max(0) as u128).sum();
+
+            let s = socialize_loss(negative_equity.into(), insurance.into(), pnl_pos_total);
+            let taken = socialize_pro_rata(&pnl, s.socialized);
+            for (p, t) in pnl.iter_mut().zip(taken) {
+                assert!(t <= (*p).max(0) as u128);
+                *p -= t as i128;
+            }
+            assert_eq!(taken.iter().sum::<u128>(), s.socialized);
+
+            let pos_after: u128 = pnl.iter().map(|&p| p.max(0) as u128).sum();
+            assert_eq!(pos_after, s.pnl_pos_total);
+            let (h_num, h_den) =
+                haircut_ratio(vault.into(), principal_total.into(), s.insurance, pos_after);
+            let paid: u128 = pnl.iter().map(|&p| effective_pnl(p, h_num, h_den)).sum();
+            let residual = (vault as u128).saturating_sub(principal_total as u128 + s.insurance);
+            assert!(paid <= residual);
+        });
+
+        // Lowering the total alone would pay 8 out of a residual of 5.
+        let s = socialize_loss(4, 0, 10);
+        assert_eq!(socialize_pro_rata(&[5, 5], s.socialized), [2, 2]);
+        let (h_num, h_den) = haircut_ratio(5, 0, 0, s.pnl_pos_total);
+        assert!(2 * effective_pnl(3, h_num, h_den) <= 5);
+    }
+
+    #[test]
+    fn fill_keeps_oi_balanced_and_cash_conserved() {
+        check(|| {
+            let mut taker = TradeAccount {
+                capital: any::<u16>() as u128,
+                ..TradeAccount::default()
+            };
+            let mut maker = TradeAccount {
+                capital: any::<u16>() as u128,
+                ..TradeAccount::default()
+            };
+            let size = any::<i32>() as i128;
+            let price = any::<u32>() as u128;
+            let (taker_bps, maker_bps) = (any_bps(), any_bps());
+
+            let fees =
+                match_fill(&mut taker, &mut maker, size, price, taker_bps, maker_bps).unwrap();
+            assert_eq!(taker.size + maker.size, 0);
+            assert_eq!(taker.cash + maker.cash, 0);
+            assert_eq!(
+                taker.fee_debt + maker.fee_debt + fees.collected,
+                fees.charged
+            );
+        });
+    }
+
+    #[test]
+    fn haircut_slack_is_bounded_over_eight_accounts() {
+        check(|| assert_haircut_slack_bounded::<u64, 8>(effective_pnl));
+    }
+
+    /// Rounds each payout up instead of down.
+    fn ceil_payout(pnl: i128, h_num: u128, h_den: u128) -> u128 {
+        (pnl.max(0) as u128).saturating_mul(h_num).div_ceil(h_den)
+    }
+
+    #[test]
+    #[should_panic(expected = "payouts exceed residual")]
+    fn rounding_up_payouts_is_caught() {
+        check(|| assert_haircut_slack_bounded::<u64, 3>(ceil_payout));
+    }
+
+    const FEES: FeeSchedule<3> = FeeSchedule {
+        tiers: [
+            FeeTier {
+                min_volume: 0,
+                taker_bps: 10,
+                maker_rebate_bps: 2,
+            },
+            FeeTier {
+                min_volume: 1_000_000,
+                taker_bps: 7,
+                maker_rebate_bps: 3,
+            },
+            FeeTier {
+                min_volume: 50_000_000,
+                taker_bps: 5,
+                maker_rebate_bps: 5,
+            },
+        ],
+        referral_bps: 2_000,
+    };
+
+    #[test]
+    fn fee_schedule_allows_rebate_equal_to_taker_fee() {
+        let mut fees = FEES;
+        fees.tiers[2].maker_rebate_bps = fees.tiers[2].taker_bps;
+        assert!(fees.is_valid());
+        let split = fees.charge(1_000_000, 50_000_000);
+        assert_eq!(split.maker_rebate, split.taker_fee);
+        assert_eq!((split.referral, split.protocol), (0, 0));
+
+        fees.tiers[2].maker_rebate_bps += 1;
+        assert!(!fees.is_valid());
+    }
+
+    #[test]
+    fn fee_schedule_splits_exactly_and_is_monotone() {
+        assert!(FEES.is_valid());
+        check(|| {
+            let (n1, n2, volume) = any::<(u64, u64, u32)>();
+            let (n1, n2) = (n1.min(n2) as u128, n1.max(n2) as u128);
+            let fees = FEES.charge(n1, volume as u128);
+            assert!(fees.maker_rebate <= fees.taker_fee);
+            assert_eq!(
+                fees.taker_fee,
+                fees.maker_rebate + fees.referral + fees.protocol
+            );
+            assert!(fees.taker_fee <= FEES.charge(n2, volume as u128).taker_fee);
+        });
+    }
+
+    #[test]
+    fn reference_swaps_preserve_k() {
+        check(|| assert_cp_swap_preserves_k::<u64, _>(swap_exact_in));
+        check(|| assert_cp_swap_exact_out_rounds_up::<u64, _>(swap_exact_out));
+    }
+
+    #[test]
+    fn exact_out_quote_buys_at_least_the_requested_amount() {
+        check(|| {
+            let (reserve_in, reserve_out, amount_out) = any::<(u32, u32, u32)>();
+            let fee_bps = any_bps();
+            let (reserve_in, reserve_out) = (reserve_in as u64, reserve_out as u64);
+            if let Some(amount_in) =
+                swap_exact_out(reserve_in, reserve_out, amount_out as u64, fee_bps)
+            {
+                let bought = swap_exact_in(reserve_in, reserve_out, amount_in, fee_bps);
+                assert!(bought.is_some_and(|b| b >= amount_out as u64));
+            }
+        });
+    }
+
+    /// Prices the swap on the full input, forgetting to deduct the fee.
+    fn fee_ignoring_swap(
+        reserve_in: u64,
+        reserve_out: u64,
+        amount_in: u64,
+        fee_bps: u64,
+    ) -> Option<u64> {
+        swap_exact_in(reserve_in, reserve_out, amount_in, 0).filter(|_| fee_bps <= 10_000)
+    }
+
+    #[test]
+    #[should_panic(expected = "swap decreases k")]
+    fn fee_ignoring_swap_is_caught() {
+        check(|| assert_cp_swap_preserves_k::<u64, _>(fee_ignoring_swap));
+    }
+
+    #[test]
+    fn lp_round_trip_never_returns_more_than_deposited() {
+        check(|| {
+            let (first_a, first_b, amount_a, amount_b) = any::<(u16, u16, u16, u16)>();
+            let mut pool = LpPool::new();
+            if pool.mint(first_a as u64, first_b as u64).is_err() {
+                return;
+            }
+            let before = pool;
+            if let Ok(shares) = pool.mint(amount_a as u64, amount_b as u64) {
+                let (out_a, out_b) = pool.burn(shares).unwrap();
+                assert!(out_a <= amount_a as u64 && out_b <= amount_b as u64);
+                assert!(pool.supply == before.supply);
+                assert!(pool.reserve_a >= before.reserve_a && pool.reserve_b >= before.reserve_b);
+            }
+        });
+    }
+
+    #[test]
+    fn donation_attack_is_rejected_or_costs_the_attacker() {
+        check(|| {
+            let (donation, deposit) = any::<(u32, u16)>();
+            // Attacker seeds the pool with the smallest deposit that mints a share.
+            let mut pool = LpPool::new();
+            let attacker = pool
+                .mint(MINIMUM_LIQUIDITY + 1, MINIMUM_LIQUIDITY + 1)
+                .unwrap();
+            pool.reserve_a += donation as u64;
+            pool.reserve_b += donation as u64;
+
+            match pool.mint(deposit as u64, deposit as u64) {
+                Ok(shares) => assert!(shares > 0),
+                Err(err) => {
+                    assert_eq!(err, LpError::ZeroShares);
+                    let (out_a, _) = pool.burn(attacker).unwrap();
+                    assert!(
+                        donation as u64 + MINIMUM_LIQUIDITY + 1 - out_a
+                            > MINIMUM_LIQUIDITY * deposit as u64
+                    );
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn fee_is_zero_at_zero_rate() {
+        check(|| {
+            assert_default_on_condition(
+                |&(_, rate_bps): &(u64, u16)| rate_bps == 0,
+                |&(amount, rate_bps)| ((amount as u128) * (rate_bps as u128) / 10_000) as u64,
+                0,
+            )
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "split does not conserve value")]
+    fn lossy_split_is_caught() {
+        check(lossy_half_split);
+    }
+
+    #[test]
+    fn counterexample_becomes_regression_test() {
+        let cx = find_counterexample(lossy_half_split).expect("lossy split must fail");
+        assert_eq!(cx.inputs().len(), 1);
+        assert_eq!(cx.inputs()[0].0, "u64");
+        let test_src = cx.regression_test();
+        assert!(test_src.contains("Paste into `crate::runtime_checks`"));
+        assert!(test_src.contains("replay(&["));
+        assert!(test_src.contains("], lossy_half_split);"));
+
+        let tape = cx.tape().to_vec();
+        let replayed = std::panic::catch_unwind(|| replay(&tape, lossy_half_split));
+        assert!(replayed.is_err());
+    }
+
+    #[test]
+    fn closure_counterexample_replays_recorded_body() {
+        let harness = || assert_two_way_split_conserves(|total| (total / 2, total / 2));
+        let cx = find_counterexample(harness).expect("lossy split must fail");
+        assert!(cx.regression_test().contains("compile_error!"));
+
+        let test_src = cx
+            .with_source("{ assert_two_way_split_conserves(|total| (total / 2, total / 2)) }")
+            .regression_test();
+        assert!(!test_src.contains("compile_error!"));
+        assert!(test_src.contains("], || { assert_two_way_split_conserves("));
+
+        let caught = std::panic::catch_unwind(|| {
+            kani_solana::check!({
+                assert_two_way_split_conserves(|total| (total / 2, total / 2));
+            })
+        });
+        assert!(caught.is_err());
+    }
+
+    kani_solana::tiered_proof! {
+        #[should_panic(expected = "halving loses odd units")]
+        fn lossy_halving_is_caught<W>() {
+            let amount = any_unsigned::<W>();
+            assert_eq!(amount / 2 + amount / 2, amount, "halving loses odd units");
+        }
+    }
+
+    // Regression test printed by the failing `width_u16` instance above, pasted
+    // as-is apart from `should_panic`.
+    #[test]
+    #[should_panic(expected = "halving loses odd units")]
+    fn lossy_halving_is_caught_width_u16_regression_9e37d8a749a96c22() {
+        // u16 = 1
+        kani_solana::runtime::replay(
+            &[
+                0x5ac75022b42ecf9a,
+                0x99913f68ec893ad,
+                0x896ddba1f29cbc8,
+                0xaff4711a71c575d,
+                0x7969c5f2a3ff4aba,
+            ],
+            || {
+                #[allow(dead_code)]
+                type W = u16;
+                {
+                    let amount = any_unsigned::<W>();
+                    assert_eq!(amount / 2 + amount / 2, amount, "halving loses odd units");
+                }
+            },
+        );
+    }
 }